@@ -25,20 +25,25 @@ fn main() {
     let mut next_action = None::<WEvent>;
 
     let mut window = env
-        .create_window::<ConceptFrame, _>(surface, None, dimensions, move |evt, mut dispatch_data| {
-            let next_actn = dispatch_data.get::<Option<WEvent>>().unwrap();
-            // Keep last event in priority order : Close > Configure > Refresh
-            let replace = match (&evt, &*next_actn) {
-                (_, &None)
-                | (_, &Some(WEvent::Refresh))
-                | (&WEvent::Configure { .. }, &Some(WEvent::Configure { .. }))
-                | (&WEvent::Close, _) => true,
-                _ => false,
-            };
-            if replace {
-                *next_actn = Some(evt);
-            }
-        })
+        .create_window::<ConceptFrame, _>(
+            surface,
+            None,
+            dimensions,
+            move |evt, mut dispatch_data| {
+                let next_actn = dispatch_data.get::<Option<WEvent>>().unwrap();
+                // Keep last event in priority order : Close > Configure > Refresh
+                let replace = match (&evt, &*next_actn) {
+                    (_, &None)
+                    | (_, &Some(WEvent::Refresh))
+                    | (&WEvent::Configure { .. }, &Some(WEvent::Configure { .. }))
+                    | (&WEvent::Close, _) => true,
+                    _ => false,
+                };
+                if replace {
+                    *next_actn = Some(evt);
+                }
+            },
+        )
         .expect("Failed to create a window !");
 
     let mut pools = DoubleMemPool::new(
@@ -137,17 +142,17 @@ fn redraw(
     }
     println!("Background draw time: {:?}", total_dur);
 
-    let rectangle = rectangle::Rectangle::new(
+    let mut rectangle = rectangle::Rectangle::new(
         (buf_x / 30, buf_y / 4),
         (buf_x - (buf_x / 30) * 2, buf_y - buf_y / 2),
-        Some((
+        Some(rectangle::Border::uniform(
             15,
             [255, 170, 20, 45],
             rectangle::Sides::TOP ^ rectangle::Sides::BOTTOM,
-            Some(10),
         )),
         Some([255, 170, 20, 45]),
     );
+    rectangle.radii = rectangle::Radii::uniform(10.0);
     let mut timer = Instant::now();
     canvas.draw(&rectangle);
     println!("Rectangle draw time: {:?}", timer.elapsed());
@@ -168,15 +173,20 @@ fn redraw(
         buf_y / 2 - text_hh as usize,
     );
 
-    let text_box = rectangle::Rectangle::new(
+    let mut text_box = rectangle::Rectangle::new(
         (
             buf_x / 2 - text.get_width() / 2 - 10,
             buf_y / 2 - text_hh as usize - 10,
         ),
         (text.get_width() + 20, text_h as usize + 20),
-        Some((3, [255, 255, 255, 255], rectangle::Sides::ALL, Some(5))),
+        Some(rectangle::Border::uniform(
+            3,
+            [255, 255, 255, 255],
+            rectangle::Sides::ALL,
+        )),
         None,
     );
+    text_box.radii = rectangle::Radii::uniform(5.0);
 
     timer = Instant::now();
     canvas.draw(&text_box);
@@ -0,0 +1,89 @@
+/// Returns the widths of `passes` box blurs that together approximate a gaussian blur with a
+/// standard deviation of `sigma`
+fn box_widths(sigma: f32, passes: usize) -> Vec<usize> {
+    let n = passes as f32;
+    let ideal = (12.0 * sigma * sigma / n + 1.0).sqrt();
+    let mut lower = ideal.floor().max(1.0) as usize;
+    if lower % 2 == 0 {
+        lower -= 1;
+    }
+    let l = lower as f32;
+    // The number of passes that use the narrower width, so the variances add up to sigma squared
+    let narrow = ((12.0 * sigma * sigma - n * l * l - 4.0 * n * l - 3.0 * n) / (-4.0 * l - 4.0))
+        .round()
+        .max(0.0) as usize;
+    (0..passes)
+        .map(|i| if i < narrow { lower } else { lower + 2 })
+        .collect()
+}
+
+/// Replaces every value of `line` with the average of the values within `radius` of it. Values
+/// beyond the ends of the line count as zero
+fn box_blur_line(line: &mut [f32], scratch: &mut Vec<f32>, radius: usize) {
+    scratch.clear();
+    scratch.extend_from_slice(line);
+    let len = line.len();
+    let scale = 1.0 / (2.0 * radius as f32 + 1.0);
+    let mut sum: f32 = scratch.iter().take(radius).sum();
+    for i in 0..len {
+        if radius < len - i {
+            sum += scratch[i + radius];
+        }
+        if i > radius {
+            sum -= scratch[i - radius - 1];
+        }
+        line[i] = sum * scale;
+    }
+}
+
+/// Blurs a single channel image of `width` by `height` values with an approximated gaussian
+/// blur. Three box blurs are run along the rows and then along the columns, so the cost does not
+/// depend on `sigma`. Values outside of the image count as zero. `sigma` is limited to the size
+/// of the image, past which the blur spreads every value over all of it anyway
+pub(crate) fn gaussian_blur(data: &mut [f32], width: usize, height: usize, sigma: f32) {
+    if sigma.is_nan() || sigma <= 0.0 || width == 0 || height == 0 {
+        return;
+    }
+    let sigma = sigma.min(width.max(height) as f32);
+    let widths = box_widths(sigma, 3);
+    let mut scratch = Vec::new();
+    for row in data.chunks_mut(width) {
+        for &box_width in &widths {
+            box_blur_line(row, &mut scratch, box_width / 2);
+        }
+    }
+    let mut column = vec![0.0; height];
+    for x in 0..width {
+        for (y, value) in column.iter_mut().enumerate() {
+            *value = data[y * width + x];
+        }
+        for &box_width in &widths {
+            box_blur_line(&mut column, &mut scratch, box_width / 2);
+        }
+        for (y, value) in column.iter().enumerate() {
+            data[y * width + x] = *value;
+        }
+    }
+}
+
+/// Blurs a single channel image of `width` by `height` values with one box blur that averages
+/// the values within `radius` of each value. Values outside of the image count as zero
+pub(crate) fn box_blur(data: &mut [f32], width: usize, height: usize, radius: usize) {
+    if radius == 0 || width == 0 || height == 0 {
+        return;
+    }
+    let mut scratch = Vec::new();
+    for row in data.chunks_mut(width) {
+        box_blur_line(row, &mut scratch, radius);
+    }
+    let mut column = vec![0.0; height];
+    for x in 0..width {
+        for (y, value) in column.iter_mut().enumerate() {
+            *value = data[y * width + x];
+        }
+        box_blur_line(&mut column, &mut scratch, radius);
+        for (y, value) in column.iter().enumerate() {
+            data[y * width + x] = *value;
+        }
+    }
+}
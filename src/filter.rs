@@ -0,0 +1,238 @@
+use blur::{box_blur, gaussian_blur};
+
+/// A 4x5 matrix that maps each color to a new color, the same as the color matrix of SVG
+/// filters. The rows compute the red, green, blue and alpha channels of the new color, from
+/// the red, green, blue and alpha channels of the old one and an offset, all from 0.0 to 1.0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorMatrix {
+    /// The 20 values of the matrix, row after row
+    pub matrix: [f32; 20],
+}
+
+impl ColorMatrix {
+    /// Creates a new ColorMatrix object
+    pub fn new(matrix: [f32; 20]) -> ColorMatrix {
+        ColorMatrix { matrix }
+    }
+
+    /// Creates a matrix that leaves colors unchanged
+    pub fn identity() -> ColorMatrix {
+        ColorMatrix::new([
+            1.0, 0.0, 0.0, 0.0, 0.0, //
+            0.0, 1.0, 0.0, 0.0, 0.0, //
+            0.0, 0.0, 1.0, 0.0, 0.0, //
+            0.0, 0.0, 0.0, 1.0, 0.0, //
+        ])
+    }
+
+    /// Creates a matrix that turns colors gray. It's argument is the amount from 0.0, which
+    /// leaves colors unchanged, to 1.0
+    pub fn grayscale(amount: f32) -> ColorMatrix {
+        ColorMatrix::saturate(1.0 - amount.max(0.0).min(1.0))
+    }
+
+    /// Creates a matrix that gives colors a brown tone. It's argument is the amount from 0.0,
+    /// which leaves colors unchanged, to 1.0
+    pub fn sepia(amount: f32) -> ColorMatrix {
+        let a = 1.0 - amount.max(0.0).min(1.0);
+        ColorMatrix::new([
+            0.393 + 0.607 * a,
+            0.769 - 0.769 * a,
+            0.189 - 0.189 * a,
+            0.0,
+            0.0,
+            0.349 - 0.349 * a,
+            0.686 + 0.314 * a,
+            0.168 - 0.168 * a,
+            0.0,
+            0.0,
+            0.272 - 0.272 * a,
+            0.534 - 0.534 * a,
+            0.131 + 0.869 * a,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+            0.0,
+        ])
+    }
+
+    /// Creates a matrix that scales the saturation of colors. It's argument is the factor where
+    /// 0.0 makes colors gray and 1.0 leaves them unchanged
+    pub fn saturate(amount: f32) -> ColorMatrix {
+        let s = amount.max(0.0);
+        ColorMatrix::new([
+            0.213 + 0.787 * s,
+            0.715 - 0.715 * s,
+            0.072 - 0.072 * s,
+            0.0,
+            0.0,
+            0.213 - 0.213 * s,
+            0.715 + 0.285 * s,
+            0.072 - 0.072 * s,
+            0.0,
+            0.0,
+            0.213 - 0.213 * s,
+            0.715 - 0.715 * s,
+            0.072 + 0.928 * s,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+            0.0,
+        ])
+    }
+
+    /// Creates a matrix that scales the brightness of colors. It's argument is the factor where
+    /// 0.0 makes colors black and 1.0 leaves them unchanged
+    pub fn brightness(amount: f32) -> ColorMatrix {
+        let b = amount.max(0.0);
+        ColorMatrix::new([
+            b, 0.0, 0.0, 0.0, 0.0, //
+            0.0, b, 0.0, 0.0, 0.0, //
+            0.0, 0.0, b, 0.0, 0.0, //
+            0.0, 0.0, 0.0, 1.0, 0.0, //
+        ])
+    }
+
+    /// Creates a matrix that scales the contrast of colors. It's argument is the factor where
+    /// 0.0 makes colors gray and 1.0 leaves them unchanged
+    pub fn contrast(amount: f32) -> ColorMatrix {
+        let c = amount.max(0.0);
+        let offset = 0.5 - 0.5 * c;
+        ColorMatrix::new([
+            c, 0.0, 0.0, 0.0, offset, //
+            0.0, c, 0.0, 0.0, offset, //
+            0.0, 0.0, c, 0.0, offset, //
+            0.0, 0.0, 0.0, 1.0, 0.0, //
+        ])
+    }
+
+    /// Creates a matrix that inverts colors. It's argument is the amount from 0.0, which leaves
+    /// colors unchanged, to 1.0
+    pub fn invert(amount: f32) -> ColorMatrix {
+        let i = amount.max(0.0).min(1.0);
+        let scale = 1.0 - 2.0 * i;
+        ColorMatrix::new([
+            scale, 0.0, 0.0, 0.0, i, //
+            0.0, scale, 0.0, 0.0, i, //
+            0.0, 0.0, scale, 0.0, i, //
+            0.0, 0.0, 0.0, 1.0, 0.0, //
+        ])
+    }
+
+    /// Returns a matrix that applies this matrix and then `other`
+    pub fn then(&self, other: &ColorMatrix) -> ColorMatrix {
+        let (a, b) = (&self.matrix, &other.matrix);
+        let mut matrix = [0.0; 20];
+        for row in 0..4 {
+            for column in 0..5 {
+                let mut value = if column == 4 { b[row * 5 + 4] } else { 0.0 };
+                for k in 0..4 {
+                    value += b[row * 5 + k] * a[k * 5 + column];
+                }
+                matrix[row * 5 + column] = value;
+            }
+        }
+        ColorMatrix::new(matrix)
+    }
+
+    /// Maps an ARGB color
+    fn apply(&self, color: [u8; 4]) -> [u8; 4] {
+        let m = &self.matrix;
+        let rgba = [color[1], color[2], color[3], color[0]];
+        let mut channels = [0; 4];
+        for (row, channel) in channels.iter_mut().enumerate() {
+            let mut value = m[row * 5 + 4];
+            for (k, c) in rgba.iter().enumerate() {
+                value += m[row * 5 + k] * f32::from(*c) / 255.0;
+            }
+            *channel = (value * 255.0).round().max(0.0).min(255.0) as u8;
+        }
+        [channels[3], channels[0], channels[1], channels[2]]
+    }
+}
+
+/// A filter that is applied to a region of the pixels already on a canvas
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Filter {
+    /// A gaussian blur with the given standard deviation in pixels
+    Blur(f32),
+    /// A box blur that averages the pixels within the given radius in pixels
+    BoxBlur(usize),
+    /// Maps the color of every pixel with a color matrix
+    ColorMatrix(ColorMatrix),
+}
+
+impl Filter {
+    /// Filters `width` by `height` ARGB colors, row after row. Blurs only spread the colors
+    /// within the region, as if the pixels around it were missing
+    pub(crate) fn apply(&self, pixels: &mut [[u8; 4]], width: usize, height: usize) {
+        match *self {
+            Filter::Blur(sigma) => {
+                blur(pixels, width, height, |data| {
+                    gaussian_blur(data, width, height, sigma)
+                });
+            }
+            Filter::BoxBlur(radius) => {
+                // A wider box averages every pixel over the whole region just the same
+                let radius = radius.min(width.max(height));
+                blur(pixels, width, height, |data| {
+                    box_blur(data, width, height, radius)
+                });
+            }
+            Filter::ColorMatrix(ref matrix) => {
+                for pixel in pixels.iter_mut() {
+                    *pixel = matrix.apply(*pixel);
+                }
+            }
+        }
+    }
+}
+
+/// Blurs each channel of the colors with premultiplied alpha and divides the result by the
+/// blurred coverage of the region, so its edges do not fade to black
+fn blur<F: Fn(&mut [f32])>(pixels: &mut [[u8; 4]], width: usize, height: usize, blur: F) {
+    let mut weights = vec![1.0; width * height];
+    blur(&mut weights);
+    let mut channels: Vec<Vec<f32>> = (0..4)
+        .map(|c| {
+            pixels
+                .iter()
+                .map(|pixel| {
+                    let alpha = f32::from(pixel[0]);
+                    if c == 0 {
+                        alpha
+                    } else {
+                        f32::from(pixel[c]) * alpha / 255.0
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    for channel in &mut channels {
+        blur(channel);
+    }
+    for (i, pixel) in pixels.iter_mut().enumerate() {
+        let weight = weights[i];
+        if weight <= 0.0 {
+            continue;
+        }
+        let alpha = channels[0][i] / weight;
+        pixel[0] = alpha.round().max(0.0).min(255.0) as u8;
+        for c in 1..4 {
+            pixel[c] = if alpha > 0.0 {
+                (channels[c][i] / weight * 255.0 / alpha)
+                    .round()
+                    .max(0.0)
+                    .min(255.0) as u8
+            } else {
+                0
+            };
+        }
+    }
+}
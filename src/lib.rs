@@ -1,4 +1,10 @@
 //! Andrew is a crate for drawing objects
+//!
+//! Every drawable places fractional coordinates the same way: pixel centers lie on whole
+//! coordinates, so pixel `(x, y)` covers the area from `(x - 0.5, y - 0.5)` to
+//! `(x + 0.5, y + 0.5)`. A circle centered on `(10.0, 10.0)` is centered on the pixel that a
+//! line through `x = 10.0` lights up. Positions and sizes in whole pixels, like those of
+//! rectangles and images, count pixels from the top-left of the canvas
 #![warn(missing_docs)]
 extern crate rusttype;
 extern crate walkdir;
@@ -8,8 +14,21 @@ extern crate xml;
 #[macro_use]
 extern crate bitflags;
 
+use std::cmp::min;
+
+use filter::Filter;
+use mask::Mask;
+
+mod blur;
+/// A module that contains functions and objects relating to filters
+pub mod filter;
 /// A module that contains functions and objects relating to lines
 pub mod line;
+/// A module that contains functions and objects relating to masks
+pub mod mask;
+/// A module that contains functions and objects relating to paints
+pub mod paint;
+mod raster;
 /// A module that contains functions and objects relating to shapes
 pub mod shapes;
 /// A module that contains functions and objects relating to text
@@ -41,6 +60,170 @@ impl Endian {
     }
 }
 
+/// Decides how many samples are taken per pixel when computing the coverage of antialiased shapes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AntialiasQuality {
+    /// Fastest, with visible steps in the coverage of shallow edges
+    Fast,
+    /// A balance between speed and accuracy
+    Good,
+    /// Slowest, with smooth coverage on all edges
+    Best,
+}
+
+impl AntialiasQuality {
+    /// Returns the number of sub-scanlines sampled per row of pixels
+    pub(crate) fn samples(self) -> usize {
+        match self {
+            AntialiasQuality::Fast => 2,
+            AntialiasQuality::Good => 4,
+            AntialiasQuality::Best => 16,
+        }
+    }
+}
+
+/// Decides how text is antialiased
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextAntialias {
+    /// Glyph pixels are either fully drawn or skipped
+    Off,
+    /// Glyph edges are blended with the background by their coverage
+    Grayscale,
+}
+
+/// Hints that drawables consult when rendering to a canvas. Drawables with their own settings for
+/// these override the hints
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderHints {
+    /// Decides whether lines and shapes are antialiased
+    pub antialias: bool,
+    /// The quality of antialiasing for shapes. Thin lines are always drawn with a single sample
+    pub antialias_quality: AntialiasQuality,
+    /// Decides how text is antialiased
+    pub text_antialias: TextAntialias,
+    /// Decides whether fractional positions are rounded to whole pixels before drawing
+    pub pixel_snap: bool,
+}
+
+impl Default for RenderHints {
+    fn default() -> RenderHints {
+        RenderHints {
+            antialias: true,
+            antialias_quality: AntialiasQuality::Good,
+            text_antialias: TextAntialias::Grayscale,
+            pixel_snap: false,
+        }
+    }
+}
+
+/// Decides how the colors of a layer are combined with the colors below it when the layer is
+/// composited, following the blend modes of CSS and SVG
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlendMode {
+    /// The layer is drawn over what is below it
+    Normal,
+    /// Multiplies the colors, which always darkens
+    Multiply,
+    /// Multiplies the inverted colors, which always lightens
+    Screen,
+    /// Multiplies dark and screens light colors of what is below the layer
+    Overlay,
+    /// Keeps the darker of the colors
+    Darken,
+    /// Keeps the lighter of the colors
+    Lighten,
+    /// Subtracts the darker from the lighter color
+    Difference,
+}
+
+impl BlendMode {
+    /// Blends one channel of a source color with one channel of the backdrop, both from 0.0 to
+    /// 1.0
+    fn blend(self, backdrop: f32, source: f32) -> f32 {
+        let screen = |b: f32, s: f32| b + s - b * s;
+        match self {
+            BlendMode::Normal => source,
+            BlendMode::Multiply => backdrop * source,
+            BlendMode::Screen => screen(backdrop, source),
+            BlendMode::Overlay => {
+                if backdrop <= 0.5 {
+                    source * 2.0 * backdrop
+                } else {
+                    screen(source, 2.0 * backdrop - 1.0)
+                }
+            }
+            BlendMode::Darken => backdrop.min(source),
+            BlendMode::Lighten => backdrop.max(source),
+            BlendMode::Difference => (backdrop - source).abs(),
+        }
+    }
+
+    /// Returns the color that a source color is drawn with over a backdrop with straight alpha.
+    /// Where the backdrop is transparent the source color is kept as it is
+    fn mix(self, backdrop: [u8; 4], source: [u8; 4]) -> [u8; 4] {
+        if self == BlendMode::Normal {
+            return source;
+        }
+        let backdrop_alpha = f32::from(backdrop[0]) / 255.0;
+        let mut color = source;
+        for c in 1..4 {
+            let (b, s) = (f32::from(backdrop[c]) / 255.0, f32::from(source[c]) / 255.0);
+            let mixed = (1.0 - backdrop_alpha) * s + backdrop_alpha * self.blend(b, s);
+            color[c] = (mixed * 255.0).round().max(0.0).min(255.0) as u8;
+        }
+        color
+    }
+}
+
+/// Decides which neighbouring pixels a flood fill spreads to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Connectivity {
+    /// Spreads to the pixels left, right, above and below
+    Four,
+    /// Also spreads to the diagonal pixels
+    Eight,
+}
+
+/// An offscreen buffer that drawing is redirected into until it is composited onto what is
+/// below it
+struct Layer {
+    /// The colors of every pixel of the canvas with premultiplied alpha
+    pixels: Vec<[u8; 4]>,
+    opacity: f32,
+    blend_mode: BlendMode,
+}
+
+impl Layer {
+    /// Returns the color of a pixel with straight alpha
+    fn color(&self, index: usize) -> [u8; 4] {
+        let pixel = self.pixels[index];
+        if pixel[0] == 0 {
+            return [0; 4];
+        }
+        let alpha = u16::from(pixel[0]);
+        let mut color = pixel;
+        for c in 1..4 {
+            color[c] = ((u16::from(pixel[c]) * 255 + alpha / 2) / alpha).min(255) as u8;
+        }
+        color
+    }
+
+    /// Draws a color with straight alpha over a pixel
+    fn blend(&mut self, index: usize, color: [u8; 4], blend_mode: BlendMode) {
+        let color = blend_mode.mix(self.color(index), color);
+        let alpha = f32::from(color[0]) / 255.0;
+        let pixel = &mut self.pixels[index];
+        for c in 0..4 {
+            let source = if c == 0 {
+                255.0 * alpha
+            } else {
+                f32::from(color[c]) * alpha
+            };
+            pixel[c] = (source + f32::from(pixel[c]) * (1.0 - alpha)).round() as u8;
+        }
+    }
+}
+
 /// The canvas object acts as a wrapper around a buffer, providing information and functions
 /// for drawing
 pub struct Canvas<'a> {
@@ -56,6 +239,13 @@ pub struct Canvas<'a> {
     pub pixel_size: usize,
     /// The endianness of the canvas
     pub endianness: Endian,
+    /// The hints that drawables consult when rendering to the canvas
+    pub hints: RenderHints,
+    /// The pushed masks, each with the coverage of every pixel of the canvas after combining it
+    /// with the masks below it
+    masks: Vec<(Mask, Vec<u8>)>,
+    /// The pushed layers, drawing goes into the last one
+    layers: Vec<Layer>,
 }
 
 impl<'a> Canvas<'a> {
@@ -80,6 +270,9 @@ impl<'a> Canvas<'a> {
             stride,
             pixel_size,
             endianness,
+            hints: RenderHints::default(),
+            masks: Vec::new(),
+            layers: Vec::new(),
         }
     }
 
@@ -88,8 +281,215 @@ impl<'a> Canvas<'a> {
         drawable.draw(self);
     }
 
+    /// Pushes a mask onto the canvas, everything drawn until it is popped is modulated by it and
+    /// by the masks pushed before it
+    pub fn push_mask(&mut self, mask: Mask) {
+        let mut coverage = vec![0; self.width * self.height];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let index = y * self.width + x;
+                let below = self.masks.last().map_or(255, |top| top.1[index]);
+                coverage[index] = (u16::from(mask.coverage(x, y)) * u16::from(below) / 255) as u8;
+            }
+        }
+        self.masks.push((mask, coverage));
+    }
+
+    /// Removes the mask that was pushed last and returns it
+    pub fn pop_mask(&mut self) -> Option<Mask> {
+        self.masks.pop().map(|(mask, _)| mask)
+    }
+
+    /// Pushes a transparent layer onto the canvas that everything is drawn into until it is
+    /// popped. The layer is then composited onto what is below it as a whole, with an opacity
+    /// from 0.0 to 1.0 and a blend mode, so overlapping drawables fade out together
+    pub fn push_layer(&mut self, opacity: f32, blend_mode: BlendMode) {
+        self.layers.push(Layer {
+            pixels: vec![[0; 4]; self.width * self.height],
+            opacity: opacity.max(0.0).min(1.0),
+            blend_mode,
+        });
+    }
+
+    /// Composites the layer that was pushed last onto what is below it and removes it. Masks
+    /// have already been applied while drawing into the layer and are not applied again
+    pub fn pop_layer(&mut self) {
+        let layer = match self.layers.pop() {
+            Some(layer) => layer,
+            None => return,
+        };
+        if layer.opacity.is_nan() || layer.opacity == 0.0 {
+            return;
+        }
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let index = y * self.width + x;
+                if layer.pixels[index][0] == 0 {
+                    continue;
+                }
+                let mut color = layer.color(index);
+                color[0] = (f32::from(color[0]) * layer.opacity).round() as u8;
+                if color[0] > 0 {
+                    self.blend_point(x, y, color, layer.blend_mode);
+                }
+            }
+        }
+    }
+
+    /// Draws a color over a pixel of the last layer, or of the buffer when there are no layers
+    fn blend_point(&mut self, x: usize, y: usize, color: [u8; 4], blend_mode: BlendMode) {
+        if let Some(layer) = self.layers.last_mut() {
+            layer.blend(y * self.width + x, color, blend_mode);
+            return;
+        }
+        let color = if blend_mode == BlendMode::Normal {
+            color
+        } else {
+            let mut backdrop = self.pixel(x, y);
+            backdrop[0] = 255;
+            blend_mode.mix(backdrop, color)
+        };
+        self.blend_buffer(x, y, color);
+    }
+
+    /// Returns the ARGB color of a pixel of the buffer
+    fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        let base = self.stride * y + self.pixel_size * x;
+        let pixel = &self.buffer[base..base + 4];
+        if self.endianness == Endian::Little {
+            [pixel[3], pixel[2], pixel[1], pixel[0]]
+        } else {
+            [pixel[0], pixel[1], pixel[2], pixel[3]]
+        }
+    }
+
+    /// Sets a pixel of the buffer to an ARGB color without blending
+    fn set_pixel(&mut self, x: usize, y: usize, color: [u8; 4]) {
+        let base = self.stride * y + self.pixel_size * x;
+        let pixel = &mut self.buffer[base..base + 4];
+        if self.endianness == Endian::Little {
+            pixel.copy_from_slice(&[color[3], color[2], color[1], color[0]]);
+        } else {
+            pixel.copy_from_slice(&color);
+        }
+    }
+
+    /// Applies a filter to the pixels of the buffer within a region. It's arguments are the
+    /// position and size of the region, which is cut off at the edges of the canvas. Masks and
+    /// layers are not used, the filter works on what has been drawn to the buffer
+    pub fn apply_filter(&mut self, pos: (usize, usize), size: (usize, usize), filter: &Filter) {
+        let x_end = min(pos.0.saturating_add(size.0), self.width);
+        let y_end = min(pos.1.saturating_add(size.1), self.height);
+        if pos.0 >= x_end || pos.1 >= y_end {
+            return;
+        }
+        let (width, height) = (x_end - pos.0, y_end - pos.1);
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in pos.1..y_end {
+            for x in pos.0..x_end {
+                pixels.push(self.pixel(x, y));
+            }
+        }
+        filter.apply(&mut pixels, width, height);
+        for (i, color) in pixels.into_iter().enumerate() {
+            self.set_pixel(pos.0 + i % width, pos.1 + i / width, color);
+        }
+    }
+
+    /// Fills the region of pixels connected to the pixel at the x and y coordinate that have
+    /// its color. It's arguments are the coordinate, the color to draw with, the largest
+    /// difference in any channel for a pixel to still count as the same color and which
+    /// neighbours the fill spreads to. The region is found on the buffer and then drawn like any
+    /// other drawable. Returns the position and size of the bounding box of the filled region,
+    /// or None when the coordinate is outside of the canvas
+    pub fn flood_fill(
+        &mut self,
+        x: usize,
+        y: usize,
+        color: [u8; 4],
+        tolerance: u8,
+        connectivity: Connectivity,
+    ) -> Option<((usize, usize), (usize, usize))> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let target = self.pixel(x, y);
+        let matches = |pixel: [u8; 4]| {
+            pixel
+                .iter()
+                .zip(target.iter())
+                .all(|(a, b)| (i16::from(*a) - i16::from(*b)).abs() <= i16::from(tolerance))
+        };
+        let width = self.width;
+        let mut filled = vec![false; width * self.height];
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (x, y, x, y);
+        let mut seeds = vec![(x, y)];
+        while let Some((x, y)) = seeds.pop() {
+            if filled[y * width + x] {
+                continue;
+            }
+            let (mut left, mut right) = (x, x);
+            while left > 0 && !filled[y * width + left - 1] && matches(self.pixel(left - 1, y)) {
+                left -= 1;
+            }
+            while right + 1 < width
+                && !filled[y * width + right + 1]
+                && matches(self.pixel(right + 1, y))
+            {
+                right += 1;
+            }
+            for fill_x in left..=right {
+                filled[y * width + fill_x] = true;
+            }
+            min_x = min(min_x, left);
+            max_x = max_x.max(right);
+            min_y = min(min_y, y);
+            max_y = max_y.max(y);
+            // Pushes a seed for every run of matching pixels next to the span
+            let (start, end) = if connectivity == Connectivity::Eight {
+                (left.saturating_sub(1), min(right + 1, width - 1))
+            } else {
+                (left, right)
+            };
+            let rows = [
+                y.checked_sub(1),
+                Some(y + 1).filter(|&row| row < self.height),
+            ];
+            for row in rows.iter().filter_map(|row| *row) {
+                let mut in_run = false;
+                for run_x in start..=end {
+                    let open = !filled[row * width + run_x] && matches(self.pixel(run_x, row));
+                    if open && !in_run {
+                        seeds.push((run_x, row));
+                    }
+                    in_run = open;
+                }
+            }
+        }
+        for fill_y in min_y..=max_y {
+            for fill_x in min_x..=max_x {
+                if filled[fill_y * width + fill_x] {
+                    self.draw_point(fill_x, fill_y, color);
+                }
+            }
+        }
+        Some(((min_x, min_y), (max_x - min_x + 1, max_y - min_y + 1)))
+    }
+
     /// Draws a pixel at the x and y coordinate
-    pub fn draw_point(&mut self, x: usize, y: usize, color: [u8; 4]) {
+    pub fn draw_point(&mut self, x: usize, y: usize, mut color: [u8; 4]) {
+        if let Some((_, coverage)) = self.masks.last() {
+            let coverage = coverage[self.width * y + x];
+            color[0] = (u16::from(color[0]) * u16::from(coverage) / 255) as u8;
+            if color[0] == 0 {
+                return;
+            }
+        }
+        self.blend_point(x, y, color, BlendMode::Normal);
+    }
+
+    /// Draws a color over a pixel of the buffer, which is treated as opaque
+    fn blend_buffer(&mut self, x: usize, y: usize, color: [u8; 4]) {
         let base = self.stride * y + self.pixel_size * x;
         if self.endianness == Endian::Little {
             if color[0] == 255 {
@@ -103,9 +503,9 @@ impl<'a> Canvas<'a> {
                     let color_diff =
                         (color[3 - c] as isize - self.buffer[base + c] as isize) as f32 * alpha;
                     let new_color = (f32::from(self.buffer[base + c]) + color_diff) as u8;
-                    self.buffer[base + c] = new_color as u8;
+                    self.buffer[base + c] = new_color;
                 }
-                self.buffer[base + 3] = 255 as u8;
+                self.buffer[base + 3] = 255;
             }
         } else if color[0] == 255 {
             self.buffer[base] = color[0];
@@ -118,9 +518,66 @@ impl<'a> Canvas<'a> {
                 let color_diff =
                     (color[c] as isize - self.buffer[base + c] as isize) as f32 * alpha;
                 let new_color = (f32::from(self.buffer[base + c]) + color_diff) as u8;
-                self.buffer[base + c] = new_color as u8;
+                self.buffer[base + c] = new_color;
+            }
+            self.buffer[base] = 255;
+        }
+    }
+
+    /// Fills a rectangle of pixels with a color, skipping the parts outside of the canvas. Opaque
+    /// colors are copied straight into the buffer instead of being blended pixel by pixel, unless
+    /// a mask or a layer is pushed
+    pub(crate) fn fill_rect(&mut self, pos: (usize, usize), size: (usize, usize), color: [u8; 4]) {
+        let x_end = min(pos.0.saturating_add(size.0), self.width);
+        let y_end = min(pos.1.saturating_add(size.1), self.height);
+        if pos.0 >= x_end || pos.1 >= y_end {
+            return;
+        }
+        if color[0] == 255 && self.masks.is_empty() && self.layers.is_empty() {
+            let pixel = if self.endianness == Endian::Little {
+                [color[3], color[2], color[1], color[0]]
+            } else {
+                color
+            };
+            for y in pos.1..y_end {
+                let base = self.stride * y;
+                let row = &mut self.buffer
+                    [base + self.pixel_size * pos.0..base + self.pixel_size * x_end];
+                for dst in row.chunks_mut(self.pixel_size) {
+                    dst[..4].copy_from_slice(&pixel);
+                }
+            }
+        } else {
+            for y in pos.1..y_end {
+                for x in pos.0..x_end {
+                    self.draw_point(x, y, color);
+                }
+            }
+        }
+    }
+
+    /// Draws a row of colors starting at a pixel, skipping the part outside of the canvas. Opaque
+    /// colors are copied straight into the buffer like in `fill_rect` unless a mask or a layer is
+    /// pushed, the others are blended pixel by pixel
+    pub(crate) fn draw_row(&mut self, pos: (usize, usize), colors: &[[u8; 4]]) {
+        if pos.0 >= self.width || pos.1 >= self.height {
+            return;
+        }
+        let colors = &colors[..min(colors.len(), self.width - pos.0)];
+        let direct = self.masks.is_empty() && self.layers.is_empty();
+        let base = self.stride * pos.1 + self.pixel_size * pos.0;
+        for (i, &color) in colors.iter().enumerate() {
+            if direct && color[0] == 255 {
+                let start = base + self.pixel_size * i;
+                let dst = &mut self.buffer[start..start + 4];
+                if self.endianness == Endian::Little {
+                    dst.copy_from_slice(&[color[3], color[2], color[1], color[0]]);
+                } else {
+                    dst.copy_from_slice(&color);
+                }
+            } else if color[0] > 0 {
+                self.draw_point(pos.0 + i, pos.1, color);
             }
-            self.buffer[base] = 255 as u8;
         }
     }
 
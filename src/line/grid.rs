@@ -0,0 +1,107 @@
+use Canvas;
+use Drawable;
+
+/// A drawable object that represents a grid of one pixel wide lines
+pub struct Grid {
+    /// Position of the top-left corner of the grid
+    pub pos: (usize, usize),
+    /// The size of the area covered by the grid
+    pub size: (usize, usize),
+    /// The number of pixels between neighbouring lines
+    pub spacing: usize,
+    /// Every `major_every`th line, counted from the first, is a major line. No line is a major
+    /// line when zero
+    pub major_every: usize,
+    /// The color of minor lines
+    pub minor_color: [u8; 4],
+    /// The color of major lines, which also colors the crossings of major and minor lines
+    pub major_color: [u8; 4],
+}
+
+impl Grid {
+    /// Creates a new Grid object
+    pub fn new(
+        pos: (usize, usize),
+        size: (usize, usize),
+        spacing: usize,
+        major_every: usize,
+        minor_color: [u8; 4],
+        major_color: [u8; 4],
+    ) -> Grid {
+        Grid {
+            pos,
+            size,
+            spacing,
+            major_every,
+            minor_color,
+            major_color,
+        }
+    }
+
+    /// Returns the offsets of the lines along one axis, and whether they are major lines
+    fn lines(&self, length: usize) -> Vec<(usize, bool)> {
+        (0..length)
+            .step_by(self.spacing)
+            .enumerate()
+            .map(|(i, offset)| (offset, self.major_every != 0 && i % self.major_every == 0))
+            .collect()
+    }
+
+    fn color(&self, major: bool) -> [u8; 4] {
+        if major {
+            self.major_color
+        } else {
+            self.minor_color
+        }
+    }
+}
+
+impl Drawable for Grid {
+    fn draw(&self, canvas: &mut Canvas) {
+        if self.spacing == 0 {
+            return;
+        }
+        let columns = self.lines(self.size.0);
+        let rows = self.lines(self.size.1);
+
+        // Rows are filled as spans between columns, and columns as spans between rows, so every
+        // crossing is drawn exactly once and translucent colors blend evenly
+        for &(y, row_major) in &rows {
+            let mut from = 0;
+            for &(x, column_major) in &columns {
+                canvas.fill_rect(
+                    (self.pos.0 + from, self.pos.1 + y),
+                    (x - from, 1),
+                    self.color(row_major),
+                );
+                canvas.fill_rect(
+                    (self.pos.0 + x, self.pos.1 + y),
+                    (1, 1),
+                    self.color(row_major || column_major),
+                );
+                from = x + 1;
+            }
+            canvas.fill_rect(
+                (self.pos.0 + from, self.pos.1 + y),
+                (self.size.0 - from, 1),
+                self.color(row_major),
+            );
+        }
+        for &(x, column_major) in &columns {
+            let mut from = 0;
+            for &(y, _) in &rows {
+                canvas.fill_rect(
+                    (self.pos.0 + x, self.pos.1 + from),
+                    (1, y - from),
+                    self.color(column_major),
+                );
+                from = y + 1;
+            }
+            canvas.fill_rect(
+                (self.pos.0 + x, self.pos.1 + from),
+                (1, self.size.1 - from),
+                self.color(column_major),
+            );
+        }
+    }
+}
@@ -0,0 +1,558 @@
+use std::cmp::{max, min};
+
+/// A module that contains functions and objects relating to grids
+pub mod grid;
+/// A module that contains functions and objects relating to polylines
+pub mod polyline;
+/// A module that contains functions and objects relating to rulers
+pub mod ruler;
+
+use std::f32::consts::PI;
+
+use paint::Paint;
+use raster::{arc_points, Rasterizer};
+use Canvas;
+use Drawable;
+
+/// The shape of the ends of wide lines and other strokes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cap {
+    /// The stroke ends flat at its end points
+    Butt,
+    /// The stroke ends in a half circle around its end points
+    Round,
+    /// The stroke ends flat, half its width past its end points
+    Square,
+}
+
+impl Cap {
+    /// Returns the outline of the cap at `point`, going from the left side of a stroke to its right
+    /// side. `direction` points out of the stroke and `half_width` is half the stroke's width
+    pub(crate) fn points(
+        self,
+        point: (f32, f32),
+        direction: (f32, f32),
+        half_width: f32,
+    ) -> Vec<(f32, f32)> {
+        let left = (direction.1 * half_width, -direction.0 * half_width);
+        match self {
+            Cap::Butt => vec![
+                (point.0 + left.0, point.1 + left.1),
+                (point.0 - left.0, point.1 - left.1),
+            ],
+            Cap::Square => {
+                let out = (direction.0 * half_width, direction.1 * half_width);
+                vec![
+                    (point.0 + left.0, point.1 + left.1),
+                    (point.0 + left.0 + out.0, point.1 + left.1 + out.1),
+                    (point.0 - left.0 + out.0, point.1 - left.1 + out.1),
+                    (point.0 - left.0, point.1 - left.1),
+                ]
+            }
+            Cap::Round => {
+                let start = left.1.atan2(left.0);
+                arc_points(point, (half_width, half_width), start, start + PI)
+            }
+        }
+    }
+}
+
+/// A shape that is drawn at the start or end of a line. Markers are oriented along the line and
+/// scale with its width
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Marker {
+    /// A filled arrowhead pointing away from the line
+    Arrow,
+    /// The outline of an arrowhead pointing away from the line
+    OpenArrow,
+    /// A filled circle centered on the end of the line
+    Circle,
+    /// A filled square centered on the end of the line
+    Square,
+    /// A bar across the end of the line
+    Bar,
+}
+
+impl Marker {
+    /// Adds the outline of the marker at `tip`, pointing in `direction`, to a rasterizer and
+    /// returns how far the line may stop short of `tip` without leaving a gap
+    fn add_outline(
+        self,
+        rasterizer: &mut Rasterizer,
+        tip: (f32, f32),
+        direction: (f32, f32),
+        width: f32,
+    ) -> f32 {
+        let size = 3.0 * width;
+        let normal = (-direction.1, direction.0);
+        let at = |along: f32, across: f32| {
+            (
+                tip.0 + direction.0 * along + normal.0 * across,
+                tip.1 + direction.1 * along + normal.1 * across,
+            )
+        };
+        match self {
+            Marker::Arrow => {
+                let length = 2.0 * size;
+                rasterizer.add_shape(&[at(0.0, 0.0), at(-length, size), at(-length, -size)]);
+                length / 2.0
+            }
+            Marker::OpenArrow => {
+                let length = 2.0 * size;
+                // The inner outline is the outer one moved back far enough for the sides to be
+                // `width` thick, cut off where it crosses the base of the arrowhead
+                let inset = width * (length * length + size * size).sqrt() / size;
+                let inner = size * (1.0 - inset / length);
+                rasterizer.add_shape(&[
+                    at(0.0, 0.0),
+                    at(-length, size),
+                    at(-length, inner),
+                    at(-inset, 0.0),
+                    at(-length, -inner),
+                    at(-length, -size),
+                ]);
+                inset
+            }
+            Marker::Circle => {
+                rasterizer.add_ellipse(tip, (size, size));
+                0.0
+            }
+            Marker::Square => {
+                rasterizer.add_shape(&[
+                    at(-size, -size),
+                    at(size, -size),
+                    at(size, size),
+                    at(-size, size),
+                ]);
+                0.0
+            }
+            Marker::Bar => {
+                let half = width / 2.0;
+                rasterizer.add_shape(&[
+                    at(-half, -size),
+                    at(half, -size),
+                    at(half, size),
+                    at(-half, size),
+                ]);
+                0.0
+            }
+        }
+    }
+}
+
+/// A drawable object that represents a line
+pub struct Line {
+    /// The first point of the line. Fractional values are honored by antialiased lines and
+    /// rounded to the nearest pixel center otherwise
+    pub pt1: (f32, f32),
+    /// The second point of the line
+    pub pt2: (f32, f32),
+    /// The paint of the line
+    pub paint: Paint,
+    /// Decides whether the line will be antialiased, the canvas' hints decide when `None`
+    pub antialiased: Option<bool>,
+    /// The width of the line in pixels
+    pub width: f32,
+    /// The shape of the ends of the line that have no marker
+    pub cap: Cap,
+    /// The marker drawn at `pt1`
+    pub start_marker: Option<Marker>,
+    /// The marker drawn at `pt2`
+    pub end_marker: Option<Marker>,
+}
+
+impl Line {
+    /// Creates a new Line object with a solid color
+    pub fn new(
+        pt1: (f32, f32),
+        pt2: (f32, f32),
+        color: [u8; 4],
+        antialiased: Option<bool>,
+    ) -> Line {
+        Line {
+            pt1,
+            pt2,
+            paint: Paint::Solid(color),
+            antialiased,
+            width: 1.0,
+            cap: Cap::Butt,
+            start_marker: None,
+            end_marker: None,
+        }
+    }
+
+    fn draw_aliased(&self, canvas: &mut Canvas, pt1: (f32, f32), pt2: (f32, f32)) {
+        let (pt1, pt2) = match clip(pt1, pt2, (canvas.width, canvas.height), 0.0) {
+            Some(clipped) => clipped,
+            None => return,
+        };
+        let (x0, y0) = (pt1.0.round() as isize, pt1.1.round() as isize);
+        let (x1, y1) = (pt2.0.round() as isize, pt2.1.round() as isize);
+        let (width, height) = (canvas.width as isize, canvas.height as isize);
+        let solid = match self.paint {
+            Paint::Solid(color) => Some(color),
+            _ => None,
+        };
+        if let (Some(color), true) = (solid, x0 == x1 || y0 == y1) {
+            let (min_x, max_x) = (max(min(x0, x1), 0), min(max(x0, x1), width - 1));
+            let (min_y, max_y) = (max(min(y0, y1), 0), min(max(y0, y1), height - 1));
+            if min_x <= max_x && min_y <= max_y {
+                canvas.fill_rect(
+                    (min_x as usize, min_y as usize),
+                    ((max_x - min_x + 1) as usize, (max_y - min_y + 1) as usize),
+                    color,
+                );
+            }
+        } else if x0 == x1 {
+            if x0 >= 0 && x0 < width {
+                let min_y = max(min(y0, y1), 0);
+                let max_y = min(max(y0, y1), height - 1);
+                for y in min_y..=max_y {
+                    canvas.draw_point(
+                        x0 as usize,
+                        y as usize,
+                        self.paint.color_at(x0 as f32, y as f32),
+                    )
+                }
+            }
+        } else if y0 == y1 {
+            if y0 >= 0 && y0 < height {
+                let min_x = max(min(x0, x1), 0);
+                let max_x = min(max(x0, x1), width - 1);
+                for x in min_x..=max_x {
+                    canvas.draw_point(
+                        x as usize,
+                        y0 as usize,
+                        self.paint.color_at(x as f32, y0 as f32),
+                    )
+                }
+            }
+        } else {
+            bresenham(x0 as i64, y0 as i64, x1 as i64, y1 as i64, |x, y| {
+                if x >= 0 && y >= 0 && x < width as i64 && y < height as i64 {
+                    canvas.draw_point(
+                        x as usize,
+                        y as usize,
+                        self.paint.color_at(x as f32, y as f32),
+                    )
+                }
+            });
+        }
+    }
+
+    fn draw_antialiased(&self, canvas: &mut Canvas, pt1: (f32, f32), pt2: (f32, f32)) {
+        let bounds = (canvas.width, canvas.height);
+        // The margin keeps the ends of clipped lines off the canvas, so the partial coverage of
+        // endpoints only shows where the line really ends
+        let (pt1, pt2) = match clip(pt1, pt2, bounds, 1.0) {
+            Some(clipped) => clipped,
+            None => return,
+        };
+        xiaolin_wu(pt1.0, pt1.1, pt2.0, pt2.1, bounds, |x, y, coverage| {
+            if x >= 0 && y >= 0 && (x as usize) < bounds.0 && (y as usize) < bounds.1 {
+                let mut color = self.paint.color_at(x as f32, y as f32);
+                color[0] = (f32::from(color[0]) * coverage) as u8;
+                canvas.draw_point(x as usize, y as usize, color)
+            }
+        });
+    }
+
+    /// Draws wide lines and lines with markers by filling their outline as one shape, so the
+    /// line and its markers blend without seams or overlaps
+    fn draw_outline(
+        &self,
+        canvas: &mut Canvas,
+        start: (f32, f32),
+        end: (f32, f32),
+        antialias: bool,
+    ) {
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        let length = (dx * dx + dy * dy).sqrt();
+        let direction = if length > 0.0 {
+            (dx / length, dy / length)
+        } else {
+            (1.0, 0.0)
+        };
+
+        let mut rasterizer = Rasterizer::new();
+        let mut start_inset = 0.0;
+        let mut end_inset = 0.0;
+        if let Some(marker) = self.start_marker {
+            let backwards = (-direction.0, -direction.1);
+            start_inset = marker.add_outline(&mut rasterizer, start, backwards, self.width);
+        }
+        if let Some(marker) = self.end_marker {
+            end_inset = marker.add_outline(&mut rasterizer, end, direction, self.width);
+        }
+        if length > start_inset + end_inset {
+            let half = self.width / 2.0;
+            let from = (
+                start.0 + direction.0 * start_inset,
+                start.1 + direction.1 * start_inset,
+            );
+            let to = (
+                end.0 - direction.0 * end_inset,
+                end.1 - direction.1 * end_inset,
+            );
+            let backwards = (-direction.0, -direction.1);
+            let start_cap = if self.start_marker.is_some() {
+                Cap::Butt
+            } else {
+                self.cap
+            };
+            let end_cap = if self.end_marker.is_some() {
+                Cap::Butt
+            } else {
+                self.cap
+            };
+            let mut outline = start_cap.points(from, backwards, half);
+            outline.extend(end_cap.points(to, direction, half));
+            rasterizer.add_shape(&outline);
+        }
+
+        rasterizer.fill(
+            (canvas.width, canvas.height),
+            canvas.hints.antialias_quality.samples(),
+            antialias,
+            |x, y, coverage| {
+                let mut color = self.paint.color_at(x as f32, y as f32);
+                color[0] = (f32::from(color[0]) * coverage) as u8;
+                canvas.draw_point(x, y, color)
+            },
+        );
+    }
+}
+
+impl Drawable for Line {
+    fn draw(&self, canvas: &mut Canvas) {
+        let finite = [self.pt1.0, self.pt1.1, self.pt2.0, self.pt2.1, self.width]
+            .iter()
+            .all(|c| c.is_finite());
+        if !finite || self.width <= 0.0 {
+            return;
+        }
+        let (pt1, pt2) = if canvas.hints.pixel_snap {
+            (
+                (self.pt1.0.round(), self.pt1.1.round()),
+                (self.pt2.0.round(), self.pt2.1.round()),
+            )
+        } else {
+            (self.pt1, self.pt2)
+        };
+        let antialias = self.antialiased.unwrap_or(canvas.hints.antialias);
+        let hairline = self.width == 1.0
+            && self.cap == Cap::Butt
+            && self.start_marker.is_none()
+            && self.end_marker.is_none();
+        if !hairline {
+            self.draw_outline(canvas, pt1, pt2, antialias);
+        } else if antialias {
+            self.draw_antialiased(canvas, pt1, pt2);
+        } else {
+            self.draw_aliased(canvas, pt1, pt2);
+        }
+    }
+}
+
+/// Cuts a line down to the part that lies on pixels within `bounds`, grown by `margin` pixels on
+/// every side, using the Cohen-Sutherland algorithm. Returns `None` when no part of it does
+fn clip(
+    pt1: (f32, f32),
+    pt2: (f32, f32),
+    bounds: (usize, usize),
+    margin: f64,
+) -> Option<((f32, f32), (f32, f32))> {
+    // Pixels reach half a pixel past the centers of the first and last pixel
+    let low = -0.5 - margin;
+    let (x_max, y_max) = (
+        bounds.0 as f64 - 0.5 + margin,
+        bounds.1 as f64 - 0.5 + margin,
+    );
+    let outside = |p: (f64, f64)| [p.0 < low, p.0 > x_max, p.1 < low, p.1 > y_max];
+    let mut p0 = (f64::from(pt1.0), f64::from(pt1.1));
+    let mut p1 = (f64::from(pt2.0), f64::from(pt2.1));
+    // The line is kept as `a * x + b * y = c`. The products of the coordinates are exact, so
+    // the points where it crosses the edges stay precise however far away its ends lie
+    let (a, b) = (p1.1 - p0.1, p0.0 - p1.0);
+    let c = a * p0.0 + b * p0.1;
+    // Each point needs at most two moves. Rounding can keep a line that grazes a corner from
+    // settling, such a line is dropped
+    for _ in 0..5 {
+        let (out0, out1) = (outside(p0), outside(p1));
+        if out0.iter().chain(&out1).all(|out| !out) {
+            return Some(((p0.0 as f32, p0.1 as f32), (p1.0 as f32, p1.1 as f32)));
+        }
+        if out0.iter().zip(&out1).any(|(a, b)| *a && *b) {
+            return None;
+        }
+        // Moves a point that lies outside onto the edge it lies beyond, which the line crosses
+        // since the other point lies on the inner side of that edge
+        let (point, out) = if out0.iter().any(|out| *out) {
+            (&mut p0, out0)
+        } else {
+            (&mut p1, out1)
+        };
+        if out[0] || out[1] {
+            let x = if out[0] { low } else { x_max };
+            *point = (x, (c - a * x) / b);
+        } else {
+            let y = if out[2] { low } else { y_max };
+            *point = ((c - b * y) / a, y);
+        }
+    }
+    None
+}
+
+/// Calls `plot` with every pixel of a one pixel wide line between two pixels
+fn bresenham<F: FnMut(i64, i64)>(mut x0: i64, mut y0: i64, x1: i64, y1: i64, mut plot: F) {
+    let dx = (x1 - x0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let dy = -((y1 - y0).abs());
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+
+    loop {
+        plot(x0, y0);
+        if x0 == x1 && y0 == y1 {
+            break;
+        };
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x0 += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y0 += sy;
+        }
+    }
+}
+
+/// Walks a one pixel wide line with flat ends between two sub-pixel points, calling `plot` with
+/// every touched pixel and the fraction of it that the line covers. Columns along the major axis
+/// that fall outside of `bounds` are skipped. A zero-length line covers no area and plots nothing.
+fn xiaolin_wu<F: FnMut(isize, isize, f32)>(
+    mut x0: f32,
+    mut y0: f32,
+    mut x1: f32,
+    mut y1: f32,
+    bounds: (usize, usize),
+    mut plot: F,
+) {
+    let steep = (y1 - y0).abs() > (x1 - x0).abs();
+    if steep {
+        std::mem::swap(&mut x0, &mut y0);
+        std::mem::swap(&mut x1, &mut y1);
+    }
+    if x0 > x1 {
+        std::mem::swap(&mut x0, &mut x1);
+        std::mem::swap(&mut y0, &mut y1);
+    }
+    let dx = x1 - x0;
+    if dx <= 0.0 {
+        return;
+    }
+    let gradient = (y1 - y0) / dx;
+    let limit = if steep {
+        bounds.1 as isize
+    } else {
+        bounds.0 as isize
+    };
+
+    // Splits the coverage of a column between the two pixels straddling `y`
+    let mut plot_column = |x: isize, y: f32, coverage: f32| {
+        let y_floor = y.floor();
+        let fract = y - y_floor;
+        let y_floor = y_floor as isize;
+        for &(y, c) in &[
+            (y_floor, coverage * (1.0 - fract)),
+            (y_floor + 1, coverage * fract),
+        ] {
+            if c > 0.0 {
+                if steep {
+                    plot(y, x, c)
+                } else {
+                    plot(x, y, c)
+                }
+            }
+        }
+    };
+    let y_at = |x: f32| y0 + gradient * (x - x0);
+
+    let first = x0.round();
+    let last = x1.round();
+    if first == last {
+        plot_column(first as isize, y_at(first), dx);
+        return;
+    }
+    // Endpoint columns are only covered from the endpoint to the column's edge
+    plot_column(first as isize, y_at(first), first + 0.5 - x0);
+    plot_column(last as isize, y_at(last), x1 - (last - 0.5));
+    let start = max(first as isize + 1, 0);
+    let end = min(last as isize - 1, limit - 1);
+    for x in start..=end {
+        plot_column(x, y_at(x as f32), 1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Endian;
+
+    const SIZE: usize = 50;
+
+    /// Draws a black line on a white canvas and returns the red channel of every pixel
+    fn draw(pt1: (f32, f32), pt2: (f32, f32), antialiased: bool) -> Vec<u8> {
+        let mut buffer = vec![255; SIZE * SIZE * 4];
+        {
+            let mut canvas = Canvas::new(&mut buffer, SIZE, SIZE, SIZE * 4, Endian::Big);
+            canvas.draw(&Line::new(pt1, pt2, [255, 0, 0, 0], Some(antialiased)));
+        }
+        buffer.chunks(4).map(|pixel| pixel[1]).collect()
+    }
+
+    #[test]
+    fn huge_lines_are_clipped_to_the_canvas() {
+        for &antialiased in &[false, true] {
+            let red = draw((-1e30, -1e30), (1e30, 1e30), antialiased);
+            assert!(red[25 * SIZE + 25] < 128);
+            let red = draw((-1e30, 0.0), (1e30, 5.0), antialiased);
+            assert!(red[2 * SIZE + 25] < 255 || red[3 * SIZE + 25] < 255);
+            let red = draw((-1e9, 10.0), (1e9, 10.0), antialiased);
+            assert!(red[10 * SIZE..11 * SIZE].iter().all(|&r| r < 128));
+            let red = draw((1e30, 1e30), (2e30, 2e30), antialiased);
+            assert!(red.iter().all(|&r| r == 255));
+        }
+    }
+
+    #[test]
+    fn degenerate_lines_do_not_panic() {
+        let nan = ::std::f32::NAN;
+        let infinity = ::std::f32::INFINITY;
+        for &antialiased in &[false, true] {
+            draw((10.0, 10.0), (10.0, 10.0), antialiased);
+            draw((10.0, 10.0), (10.2, 10.1), antialiased);
+            draw((0.0, 0.0), (0.0, 49.0), antialiased);
+            draw((-0.5, 49.5), (49.5, 49.5), antialiased);
+            draw((-5.0, -5.0), (-1.0, -1.0), antialiased);
+            for &(pt1, pt2) in &[
+                ((nan, 0.0), (10.0, 10.0)),
+                ((0.0, 0.0), (infinity, 10.0)),
+                ((-infinity, 5.0), (infinity, 5.0)),
+            ] {
+                let red = draw(pt1, pt2, antialiased);
+                assert!(red.iter().all(|&r| r == 255));
+            }
+        }
+    }
+
+    #[test]
+    fn antialiased_endpoints_are_partly_covered() {
+        let red = draw((2.0, 5.0), (7.25, 5.0), true);
+        let row = &red[5 * SIZE..6 * SIZE];
+        assert_eq!(row[4], 0);
+        assert!(row[7] > 0 && row[7] < 255);
+        assert_eq!(row[8], 255);
+    }
+}
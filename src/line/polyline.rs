@@ -0,0 +1,104 @@
+use line::{Cap, Marker};
+use paint::Paint;
+use raster::Rasterizer;
+use Canvas;
+use Drawable;
+
+/// A drawable object that represents a line through several points, with mitered corners and
+/// optional markers at its ends
+pub struct Polyline {
+    /// The points the line runs through, in order
+    pub points: Vec<(f32, f32)>,
+    /// The paint of the line
+    pub paint: Paint,
+    /// Decides whether the line will be antialiased, the canvas' hints decide when `None`
+    pub antialiased: Option<bool>,
+    /// The width of the line in pixels
+    pub width: f32,
+    /// The shape of the ends of the line that have no marker
+    pub cap: Cap,
+    /// The marker drawn at the first point, pointing away from the first segment
+    pub start_marker: Option<Marker>,
+    /// The marker drawn at the last point, pointing away from the last segment
+    pub end_marker: Option<Marker>,
+}
+
+impl Polyline {
+    /// Creates a new Polyline object with a solid color
+    pub fn new(points: Vec<(f32, f32)>, color: [u8; 4], antialiased: Option<bool>) -> Polyline {
+        Polyline {
+            points,
+            paint: Paint::Solid(color),
+            antialiased,
+            width: 1.0,
+            cap: Cap::Butt,
+            start_marker: None,
+            end_marker: None,
+        }
+    }
+}
+
+/// Returns the length of a segment and the direction from its first to its second point
+fn segment(from: (f32, f32), to: (f32, f32)) -> (f32, (f32, f32)) {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length = (dx * dx + dy * dy).sqrt();
+    (length, (dx / length, dy / length))
+}
+
+impl Drawable for Polyline {
+    fn draw(&self, canvas: &mut Canvas) {
+        let finite = self
+            .points
+            .iter()
+            .all(|point| point.0.is_finite() && point.1.is_finite());
+        if !finite || !self.width.is_finite() || self.width <= 0.0 {
+            return;
+        }
+        let mut points: Vec<(f32, f32)> = if canvas.hints.pixel_snap {
+            self.points
+                .iter()
+                .map(|point| (point.0.round(), point.1.round()))
+                .collect()
+        } else {
+            self.points.clone()
+        };
+        points.dedup();
+        if points.len() < 2 {
+            return;
+        }
+
+        let antialias = self.antialiased.unwrap_or(canvas.hints.antialias);
+        let mut rasterizer = Rasterizer::new();
+        let last = points.len() - 1;
+        let (first_length, first_direction) = segment(points[1], points[0]);
+        let (last_length, last_direction) = segment(points[last - 1], points[last]);
+        let start_inset = self.start_marker.map_or(0.0, |marker| {
+            marker.add_outline(&mut rasterizer, points[0], first_direction, self.width)
+        });
+        let end_inset = self.end_marker.map_or(0.0, |marker| {
+            marker.add_outline(&mut rasterizer, points[last], last_direction, self.width)
+        });
+
+        // The ends of the line are pulled back under their markers like those of a `Line`
+        let pull = |point: (f32, f32), direction: (f32, f32), inset: f32, length: f32| {
+            let inset = inset.min(length);
+            (point.0 - direction.0 * inset, point.1 - direction.1 * inset)
+        };
+        if last == 1 && first_length <= start_inset + end_inset {
+            rasterizer.draw(canvas, &self.paint, antialias);
+            return;
+        }
+        points[0] = pull(points[0], first_direction, start_inset, first_length);
+        points[last] = pull(points[last], last_direction, end_inset, last_length);
+        rasterizer.add_stroke(&points, false, self.width, Cap::Butt);
+
+        let half = self.width / 2.0;
+        if self.start_marker.is_none() {
+            rasterizer.add_shape(&self.cap.points(points[0], first_direction, half));
+        }
+        if self.end_marker.is_none() {
+            rasterizer.add_shape(&self.cap.points(points[last], last_direction, half));
+        }
+        rasterizer.draw(canvas, &self.paint, antialias);
+    }
+}
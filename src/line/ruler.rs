@@ -0,0 +1,115 @@
+use text::Text;
+use Canvas;
+use Drawable;
+
+/// The direction a ruler runs in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Orientation {
+    /// The ruler runs from left to right with its ticks hanging down
+    Horizontal,
+    /// The ruler runs from top to bottom with its ticks pointing right
+    Vertical,
+}
+
+/// A drawable object that represents a ruler with tick marks and labelled major ticks
+pub struct Ruler<'a> {
+    /// Position of the start of the ruler's edge
+    pub pos: (usize, usize),
+    /// The length of the ruler in pixels
+    pub length: usize,
+    /// The direction the ruler runs in
+    pub orientation: Orientation,
+    /// The number of pixels between neighbouring ticks
+    pub spacing: usize,
+    /// Every `major_every`th tick, counted from the first, is a major tick. No tick is a major tick
+    /// when zero
+    pub major_every: usize,
+    /// The lengths of minor and major ticks
+    pub tick_lengths: (usize, usize),
+    /// The color of the ruler's edge, ticks and labels
+    pub color: [u8; 4],
+    /// The font used for the labels of major ticks, no labels are drawn when `None`
+    pub font_data: Option<&'a [u8]>,
+    /// The height of the labels
+    pub label_height: f32,
+    /// The value that the label of each major tick increases by
+    pub label_step: f32,
+}
+
+impl<'a> Ruler<'a> {
+    /// Creates a new Ruler object without labels, with major ticks labelled in pixels once a font
+    /// is set
+    pub fn new(
+        pos: (usize, usize),
+        length: usize,
+        orientation: Orientation,
+        spacing: usize,
+        major_every: usize,
+        color: [u8; 4],
+    ) -> Ruler<'a> {
+        Ruler {
+            pos,
+            length,
+            orientation,
+            spacing,
+            major_every,
+            tick_lengths: (4, 8),
+            color,
+            font_data: None,
+            label_height: 10.0,
+            label_step: (spacing * major_every) as f32,
+        }
+    }
+
+    /// Fills a span given in the ruler's own coordinates, `along` and `across` its edge
+    fn fill(&self, canvas: &mut Canvas, along: usize, across: usize, length: (usize, usize)) {
+        match self.orientation {
+            Orientation::Horizontal => canvas.fill_rect(
+                (self.pos.0 + along, self.pos.1 + across),
+                length,
+                self.color,
+            ),
+            Orientation::Vertical => canvas.fill_rect(
+                (self.pos.0 + across, self.pos.1 + along),
+                (length.1, length.0),
+                self.color,
+            ),
+        }
+    }
+}
+
+impl<'a> Drawable for Ruler<'a> {
+    fn draw(&self, canvas: &mut Canvas) {
+        self.fill(canvas, 0, 0, (self.length, 1));
+        if self.spacing == 0 {
+            return;
+        }
+        for (i, offset) in (0..self.length).step_by(self.spacing).enumerate() {
+            let major = self.major_every != 0 && i % self.major_every == 0;
+            let tick_length = if major {
+                self.tick_lengths.1
+            } else {
+                self.tick_lengths.0
+            };
+            self.fill(canvas, offset, 1, (1, tick_length));
+
+            if let (true, Some(font_data)) = (major, self.font_data) {
+                let value = (i / self.major_every) as f32 * self.label_step;
+                let (along, across) = (offset + 2, self.tick_lengths.0 + 1);
+                let pos = match self.orientation {
+                    Orientation::Horizontal => (self.pos.0 + along, self.pos.1 + across),
+                    Orientation::Vertical => (self.pos.0 + across, self.pos.1 + along),
+                };
+                let label = Text::new(
+                    pos,
+                    self.color,
+                    font_data,
+                    self.label_height,
+                    1.0,
+                    value.to_string(),
+                );
+                canvas.draw(&label);
+            }
+        }
+    }
+}
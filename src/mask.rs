@@ -0,0 +1,63 @@
+use raster::Rasterizer;
+use shapes::path::Path;
+use AntialiasQuality;
+
+/// An alpha mask that modulates everything drawn on a canvas while it is pushed onto it. Each
+/// value is the coverage of one pixel, from 0 which hides the pixel to 255 which leaves it as
+/// drawn. Pixels outside of the mask are hidden
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mask {
+    /// Position on the canvas of the top-left corner of the mask
+    pub pos: (usize, usize),
+    /// The width of the mask in pixels
+    pub width: usize,
+    /// The height of the mask in pixels
+    pub height: usize,
+    /// The coverage of each pixel, row after row
+    pub data: Vec<u8>,
+}
+
+impl Mask {
+    /// Creates a new Mask object from an A8 coverage buffer
+    pub fn new(pos: (usize, usize), width: usize, height: usize, data: Vec<u8>) -> Mask {
+        assert!(
+            data.len() == width * height,
+            "Incorrect Dimensions - Data does not hold one value per pixel"
+        );
+        Mask {
+            pos,
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// Creates a mask of the given size at the top-left corner of the canvas that shows the
+    /// fill of a path, following its fill rule. The fill and stroke paints of the path are
+    /// ignored
+    pub fn from_path(path: &Path, size: (usize, usize), antialias: bool) -> Mask {
+        let mut rasterizer = Rasterizer::new();
+        rasterizer.set_fill_rule(path.fill_rule);
+        for (points, _) in &path.flatten() {
+            rasterizer.add_polygon(points);
+        }
+        let mut data = vec![0; size.0 * size.1];
+        let samples = AntialiasQuality::Good.samples();
+        rasterizer.fill(size, samples, antialias, |x, y, coverage| {
+            data[y * size.0 + x] = (coverage * 255.0).round() as u8;
+        });
+        Mask::new((0, 0), size.0, size.1, data)
+    }
+
+    /// Returns the coverage of a pixel on the canvas
+    pub(crate) fn coverage(&self, x: usize, y: usize) -> u8 {
+        if x < self.pos.0 || y < self.pos.1 {
+            return 0;
+        }
+        let (x, y) = (x - self.pos.0, y - self.pos.1);
+        if x >= self.width || y >= self.height {
+            return 0;
+        }
+        self.data[y * self.width + x]
+    }
+}
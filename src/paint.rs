@@ -0,0 +1,506 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use shapes::image::PixelFormat;
+
+/// A color that a gradient passes through at a given offset
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorStop {
+    /// The position of the stop along the gradient, from 0.0 to 1.0
+    pub offset: f32,
+    /// The color of the gradient at the stop
+    pub color: [u8; 4],
+}
+
+impl ColorStop {
+    /// Creates a new ColorStop object
+    pub fn new(offset: f32, color: [u8; 4]) -> ColorStop {
+        ColorStop { offset, color }
+    }
+}
+
+/// A list of color stops that colors are interpolated between
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    /// The color stops of the gradient, ordered by offset
+    pub stops: Vec<ColorStop>,
+}
+
+impl Gradient {
+    /// Creates a new Gradient object, the stops are sorted by their offset
+    pub fn new(mut stops: Vec<ColorStop>) -> Gradient {
+        stops.sort_by(|a, b| {
+            a.offset
+                .partial_cmp(&b.offset)
+                .unwrap_or(::std::cmp::Ordering::Equal)
+        });
+        Gradient { stops }
+    }
+
+    /// Creates a gradient going from one color to another
+    pub fn two_color(start: [u8; 4], end: [u8; 4]) -> Gradient {
+        Gradient::new(vec![ColorStop::new(0.0, start), ColorStop::new(1.0, end)])
+    }
+
+    /// Returns the color of the gradient at offset `t`. Offsets before the first or after the
+    /// last stop take the color of that stop
+    pub fn color_at(&self, t: f32) -> [u8; 4] {
+        let channels = self.channels_at(t);
+        [
+            channels[0].round() as u8,
+            channels[1].round() as u8,
+            channels[2].round() as u8,
+            channels[3].round() as u8,
+        ]
+    }
+
+    /// Returns the color of the gradient at offset `t` before it is rounded to whole channel
+    /// values
+    fn channels_at(&self, t: f32) -> [f32; 4] {
+        let first = match self.stops.first() {
+            Some(stop) => stop,
+            None => return [0.0; 4],
+        };
+        if t.is_nan() || t <= first.offset {
+            return channels(first.color);
+        }
+        for pair in self.stops.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            if t <= b.offset {
+                let span = b.offset - a.offset;
+                if span <= 0.0 {
+                    return channels(b.color);
+                }
+                return lerp_color(a.color, b.color, (t - a.offset) / span);
+            }
+        }
+        channels(self.stops[self.stops.len() - 1].color)
+    }
+
+    /// Returns the color at offset `t` after spreading it, dithered for the pixel that contains
+    /// the point `(x, y)` when `dither` is set
+    fn sample(&self, spread: Spread, dither: bool, t: f32, x: f32, y: f32) -> [u8; 4] {
+        if !dither {
+            return self.color_at(spread.apply(t));
+        }
+        // A 4x4 ordered dither spreads the rounding error of each pixel over its neighbours
+        const BAYER: [[f32; 4]; 4] = [
+            [0.0, 8.0, 2.0, 10.0],
+            [12.0, 4.0, 14.0, 6.0],
+            [3.0, 11.0, 1.0, 9.0],
+            [15.0, 7.0, 13.0, 5.0],
+        ];
+        let threshold = BAYER[(y.floor() as i64 & 3) as usize][(x.floor() as i64 & 3) as usize];
+        let offset = (threshold + 0.5) / 16.0 - 0.5;
+        let channels = self.channels_at(spread.apply(t));
+        let mut color = [0; 4];
+        for (c, channel) in color.iter_mut().zip(&channels) {
+            *c = (channel + offset).round().max(0.0).min(255.0) as u8;
+        }
+        color
+    }
+
+    fn map_colors<F: Fn([u8; 4]) -> [u8; 4]>(&mut self, f: F) {
+        for stop in &mut self.stops {
+            stop.color = f(stop.color);
+        }
+    }
+}
+
+/// Decides how a gradient continues beyond its first and last stop
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Spread {
+    /// The colors of the first and last stop continue forever
+    Pad,
+    /// The gradient starts over after its last stop
+    Repeat,
+    /// The gradient runs backwards after its last stop, then forwards again
+    Reflect,
+}
+
+impl Spread {
+    /// Maps an offset along a gradient into the range between 0.0 and 1.0
+    pub(crate) fn apply(self, t: f32) -> f32 {
+        match self {
+            Spread::Pad => t,
+            Spread::Repeat => t - t.floor(),
+            Spread::Reflect => {
+                let t = t.abs() % 2.0;
+                if t > 1.0 {
+                    2.0 - t
+                } else {
+                    t
+                }
+            }
+        }
+    }
+}
+
+/// A gradient that changes color along the line from `start` to `end` and stays the same across
+/// it. The stops of the gradient are placed along that line, offset 0.0 at `start` and 1.0 at
+/// `end`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearGradient {
+    /// The point on the canvas where the gradient starts
+    pub start: (f32, f32),
+    /// The point on the canvas where the gradient ends
+    pub end: (f32, f32),
+    /// The colors of the gradient
+    pub gradient: Gradient,
+    /// How the gradient continues before `start` and after `end`
+    pub spread: Spread,
+    /// Decides whether the gradient is dithered to hide banding between close colors
+    pub dither: bool,
+}
+
+impl LinearGradient {
+    /// Creates a new LinearGradient object that pads its colors beyond its ends
+    pub fn new(start: (f32, f32), end: (f32, f32), gradient: Gradient) -> LinearGradient {
+        LinearGradient {
+            start,
+            end,
+            gradient,
+            spread: Spread::Pad,
+            dither: false,
+        }
+    }
+
+    /// Returns the color of the gradient at a point on the canvas
+    pub fn color_at(&self, x: f32, y: f32) -> [u8; 4] {
+        let (dx, dy) = (self.end.0 - self.start.0, self.end.1 - self.start.1);
+        let length = dx * dx + dy * dy;
+        let t = if length > 0.0 {
+            ((x - self.start.0) * dx + (y - self.start.1) * dy) / length
+        } else {
+            0.0
+        };
+        self.gradient.sample(self.spread, self.dither, t, x, y)
+    }
+}
+
+/// A gradient that changes color outwards from a focal point to a circle. Offset 0.0 of the
+/// gradient lies on the focal point and offset 1.0 on the circle
+#[derive(Debug, Clone, PartialEq)]
+pub struct RadialGradient {
+    /// The center of the circle
+    pub center: (f32, f32),
+    /// The radius of the circle
+    pub radius: f32,
+    /// The point the gradient starts from, the center of the circle when `None`. Focal points
+    /// outside of the circle are moved onto its edge
+    pub focal: Option<(f32, f32)>,
+    /// The colors of the gradient
+    pub gradient: Gradient,
+    /// How the gradient continues outside of the circle
+    pub spread: Spread,
+    /// Decides whether the gradient is dithered to hide banding between close colors
+    pub dither: bool,
+}
+
+impl RadialGradient {
+    /// Creates a new RadialGradient object that starts from the center of the circle and pads
+    /// its colors outside of it
+    pub fn new(center: (f32, f32), radius: f32, gradient: Gradient) -> RadialGradient {
+        RadialGradient {
+            center,
+            radius,
+            focal: None,
+            gradient,
+            spread: Spread::Pad,
+            dither: false,
+        }
+    }
+
+    /// Returns the color of the gradient at a point on the canvas
+    pub fn color_at(&self, x: f32, y: f32) -> [u8; 4] {
+        let radius = self.radius.abs();
+        let t = if radius <= 0.0 {
+            0.0
+        } else if let Some(focal) = self.focal {
+            let mut d = (self.center.0 - focal.0, self.center.1 - focal.1);
+            let distance = (d.0 * d.0 + d.1 * d.1).sqrt();
+            let limit = radius * 0.999;
+            if distance > limit {
+                d = (d.0 * limit / distance, d.1 * limit / distance);
+            }
+            let focal = (self.center.0 - d.0, self.center.1 - d.1);
+            // The point lies on the circle that is `t` of the way from the focal point to the
+            // outer circle, in both position and radius
+            let q = (x - focal.0, y - focal.1);
+            let a = d.0 * d.0 + d.1 * d.1 - radius * radius;
+            let b = q.0 * d.0 + q.1 * d.1;
+            let c = q.0 * q.0 + q.1 * q.1;
+            (b - (b * b - a * c).max(0.0).sqrt()) / a
+        } else {
+            let (dx, dy) = (x - self.center.0, y - self.center.1);
+            (dx * dx + dy * dy).sqrt() / radius
+        };
+        self.gradient.sample(self.spread, self.dither, t, x, y)
+    }
+}
+
+/// A gradient that changes color around a center point. Angles are in radians, start at the
+/// positive x axis and increase clockwise
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConicGradient {
+    /// The point the gradient turns around
+    pub center: (f32, f32),
+    /// The angle where offset 0.0 of the gradient lies
+    pub start_angle: f32,
+    /// The angle from `start_angle` to where offset 1.0 of the gradient lies, a full turn by
+    /// default
+    pub sweep: f32,
+    /// The colors of the gradient
+    pub gradient: Gradient,
+    /// How the gradient continues when `sweep` is less than a full turn
+    pub spread: Spread,
+    /// Decides whether the gradient is dithered to hide banding between close colors
+    pub dither: bool,
+}
+
+impl ConicGradient {
+    /// Creates a new ConicGradient object that sweeps a full turn
+    pub fn new(center: (f32, f32), start_angle: f32, gradient: Gradient) -> ConicGradient {
+        ConicGradient {
+            center,
+            start_angle,
+            sweep: 2.0 * PI,
+            gradient,
+            spread: Spread::Pad,
+            dither: false,
+        }
+    }
+
+    /// Returns the color of the gradient at a point on the canvas
+    pub fn color_at(&self, x: f32, y: f32) -> [u8; 4] {
+        let angle = (y - self.center.1).atan2(x - self.center.0) - self.start_angle;
+        let turn = angle / (2.0 * PI);
+        let t = if self.sweep != 0.0 {
+            (turn - turn.floor()) * 2.0 * PI / self.sweep
+        } else {
+            0.0
+        };
+        self.gradient.sample(self.spread, self.dither, t, x, y)
+    }
+}
+
+/// Decides how a pattern repeats its image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Repeat {
+    /// The image is tiled in both directions
+    Repeat,
+    /// The image is tiled in a single row
+    RepeatX,
+    /// The image is tiled in a single column
+    RepeatY,
+    /// The image is tiled in both directions and every other tile is mirrored, so tiles meet
+    /// seamlessly
+    Reflect,
+    /// The image is drawn once, the paint is transparent around it
+    NoRepeat,
+}
+
+/// A paint that tiles an image, made of pixels with 8 bit channels and straight alpha. Each
+/// pixel takes the color of the image pixel it lies in
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    /// The pixels of the image, row after row. The data is shared between clones of the pattern
+    pub data: Arc<[u8]>,
+    /// The width of the image in pixels
+    pub width: usize,
+    /// The height of the image in pixels
+    pub height: usize,
+    /// The number of bytes between the starts of neighbouring rows
+    pub stride: usize,
+    /// The order of the channels of each pixel
+    pub format: PixelFormat,
+    /// How the image repeats
+    pub repeat: Repeat,
+    /// Position on the canvas of the top-left corner of the first tile
+    pub offset: (f32, f32),
+    /// An affine transform `[a, b, c, d, e, f]`, like SVG's `matrix()`, that maps points in the
+    /// image to `(a * x + c * y + e, b * x + d * y + f)` before they are moved by `offset`. The
+    /// image is not transformed when `None`
+    pub transform: Option<[f32; 6]>,
+}
+
+impl Pattern {
+    /// Creates a new Pattern object that repeats the image in both directions from the top-left
+    /// corner of the canvas
+    pub fn new(
+        data: Vec<u8>,
+        width: usize,
+        height: usize,
+        stride: usize,
+        format: PixelFormat,
+    ) -> Pattern {
+        assert!(
+            stride >= width * 4,
+            "Incorrect Dimensions - Stride is less than four bytes per pixel"
+        );
+        assert!(
+            height == 0 || data.len() >= stride * (height - 1) + width * 4,
+            "Incorrect Dimensions - Data is too short for the image"
+        );
+        Pattern {
+            data: Arc::from(data),
+            width,
+            height,
+            stride,
+            format,
+            repeat: Repeat::Repeat,
+            offset: (0.0, 0.0),
+            transform: None,
+        }
+    }
+
+    /// Returns the color of the pattern at a point on the canvas
+    pub fn color_at(&self, x: f32, y: f32) -> [u8; 4] {
+        if self.width == 0 || self.height == 0 {
+            return [0; 4];
+        }
+        let (mut x, mut y) = (x - self.offset.0, y - self.offset.1);
+        if let Some([a, b, c, d, e, f]) = self.transform {
+            let determinant = a * d - b * c;
+            if determinant == 0.0 {
+                return [0; 4];
+            }
+            let (px, py) = (x - e, y - f);
+            x = (d * px - c * py) / determinant;
+            y = (a * py - b * px) / determinant;
+        }
+        if !x.is_finite() || !y.is_finite() {
+            return [0; 4];
+        }
+        let (repeat_x, repeat_y) = match self.repeat {
+            Repeat::Repeat | Repeat::Reflect => (true, true),
+            Repeat::RepeatX => (true, false),
+            Repeat::RepeatY => (false, true),
+            Repeat::NoRepeat => (false, false),
+        };
+        let reflect = self.repeat == Repeat::Reflect;
+        // Pixels of the image are centered on whole coordinates like those of the canvas
+        let column = match wrap((x + 0.5).floor(), self.width, repeat_x, reflect) {
+            Some(column) => column,
+            None => return [0; 4],
+        };
+        let row = match wrap((y + 0.5).floor(), self.height, repeat_y, reflect) {
+            Some(row) => row,
+            None => return [0; 4],
+        };
+        let base = row * self.stride + column * 4;
+        self.format.to_argb(&self.data[base..base + 4])
+    }
+
+    fn map_colors<F: Fn([u8; 4]) -> [u8; 4]>(&mut self, f: F) {
+        let mut data = Vec::with_capacity(self.width * self.height * 4);
+        for row in 0..self.height {
+            for column in 0..self.width {
+                let base = row * self.stride + column * 4;
+                let color = f(self.format.to_argb(&self.data[base..base + 4]));
+                data.extend_from_slice(&color);
+            }
+        }
+        self.data = Arc::from(data);
+        self.stride = self.width * 4;
+        self.format = PixelFormat::Argb;
+    }
+}
+
+/// Returns the pixel that a coordinate falls on along an image `len` pixels long, or `None` when
+/// it falls outside of an image that does not repeat
+fn wrap(coordinate: f32, len: usize, repeat: bool, reflect: bool) -> Option<usize> {
+    let len = len as f32;
+    if !repeat {
+        return if coordinate >= 0.0 && coordinate < len {
+            Some(coordinate as usize)
+        } else {
+            None
+        };
+    }
+    let tile = (coordinate / len).floor();
+    let mut position = coordinate - tile * len;
+    if reflect && tile % 2.0 != 0.0 {
+        position = len - 1.0 - position;
+    }
+    Some((position.max(0.0) as usize).min(len as usize - 1))
+}
+
+/// What a shape is filled or stroked with
+#[derive(Debug, Clone, PartialEq)]
+pub enum Paint {
+    /// A single color
+    Solid([u8; 4]),
+    /// A linear gradient
+    LinearGradient(LinearGradient),
+    /// A radial gradient
+    RadialGradient(RadialGradient),
+    /// A conic gradient
+    ConicGradient(ConicGradient),
+    /// A repeated image
+    Pattern(Pattern),
+}
+
+impl Paint {
+    /// Returns the color of the paint at a point on the canvas. Pixels are painted with the
+    /// color at their center, which lies on whole coordinates
+    pub fn color_at(&self, x: f32, y: f32) -> [u8; 4] {
+        match *self {
+            Paint::Solid(color) => color,
+            Paint::LinearGradient(ref gradient) => gradient.color_at(x, y),
+            Paint::RadialGradient(ref gradient) => gradient.color_at(x, y),
+            Paint::ConicGradient(ref gradient) => gradient.color_at(x, y),
+            Paint::Pattern(ref pattern) => pattern.color_at(x, y),
+        }
+    }
+
+    /// Returns a paint with every color of this one changed by `f`
+    pub(crate) fn map_colors<F: Fn([u8; 4]) -> [u8; 4]>(&self, f: F) -> Paint {
+        let mut paint = self.clone();
+        match paint {
+            Paint::Solid(ref mut color) => *color = f(*color),
+            Paint::LinearGradient(ref mut gradient) => gradient.gradient.map_colors(f),
+            Paint::RadialGradient(ref mut gradient) => gradient.gradient.map_colors(f),
+            Paint::ConicGradient(ref mut gradient) => gradient.gradient.map_colors(f),
+            Paint::Pattern(ref mut pattern) => pattern.map_colors(f),
+        }
+        paint
+    }
+}
+
+impl Default for Paint {
+    fn default() -> Paint {
+        Paint::Solid([0, 0, 0, 0])
+    }
+}
+
+impl From<[u8; 4]> for Paint {
+    fn from(color: [u8; 4]) -> Paint {
+        Paint::Solid(color)
+    }
+}
+
+fn channels(color: [u8; 4]) -> [f32; 4] {
+    [
+        f32::from(color[0]),
+        f32::from(color[1]),
+        f32::from(color[2]),
+        f32::from(color[3]),
+    ]
+}
+
+/// Interpolates between two colors with premultiplied alpha, so fading into a transparent color
+/// does not darken the colors in between
+fn lerp_color(a: [u8; 4], b: [u8; 4], t: f32) -> [f32; 4] {
+    let (alpha_a, alpha_b) = (f32::from(a[0]) / 255.0, f32::from(b[0]) / 255.0);
+    let alpha = alpha_a + (alpha_b - alpha_a) * t;
+    let mut color = [alpha * 255.0, 0.0, 0.0, 0.0];
+    if alpha > 0.0 {
+        for c in 1..4 {
+            let from = f32::from(a[c]) * alpha_a;
+            let to = f32::from(b[c]) * alpha_b;
+            color[c] = ((from + (to - from) * t) / alpha).min(255.0);
+        }
+    }
+    color
+}
@@ -0,0 +1,128 @@
+use std::f32::consts::PI;
+
+use line::Cap;
+use paint::Paint;
+use raster::{arc_angles, Rasterizer};
+use Canvas;
+use Drawable;
+
+/// A drawable object that represents a stroked part of the perimeter of an axis-aligned ellipse.
+/// Angles are in radians, start at the positive x axis and increase clockwise
+pub struct Arc {
+    /// Position of the center of the ellipse the arc lies on
+    pub center: (f32, f32),
+    /// The horizontal and vertical radius of the ellipse the arc lies on
+    pub radii: (f32, f32),
+    /// The angle the arc starts at
+    pub start_angle: f32,
+    /// The angle the arc ends at, arcs sweep counterclockwise when it is less than `start_angle`
+    pub end_angle: f32,
+    /// The width of the stroke, centered on the perimeter of the ellipse
+    pub width: f32,
+    /// The paint of the stroke of the arc
+    pub stroke: Paint,
+    /// The shape of the ends of the arc
+    pub cap: Cap,
+    /// Decides whether the arc will be antialiased, the canvas' hints decide when `None`
+    pub antialiased: Option<bool>,
+}
+
+impl Arc {
+    /// Creates a new Arc object with a solid color
+    pub fn new(
+        center: (f32, f32),
+        radii: (f32, f32),
+        start_angle: f32,
+        end_angle: f32,
+        width: f32,
+        color: [u8; 4],
+    ) -> Arc {
+        Arc {
+            center,
+            radii,
+            start_angle,
+            end_angle,
+            width,
+            stroke: Paint::Solid(color),
+            cap: Cap::Butt,
+            antialiased: None,
+        }
+    }
+
+    /// Returns the outline of the stroke around an ellipse, following its normals on both sides
+    fn outline(&self, center: (f32, f32), radii: (f32, f32)) -> Vec<(f32, f32)> {
+        let sweep = (self.end_angle - self.start_angle)
+            .max(-2.0 * PI)
+            .min(2.0 * PI);
+        let half = self.width / 2.0;
+        let clockwise = if sweep < 0.0 { -1.0 } else { 1.0 };
+        // The point, outward normal and forward tangent at an angle
+        let frame = |angle: f32| {
+            let (sin, cos) = angle.sin_cos();
+            let point = (center.0 + radii.0 * cos, center.1 + radii.1 * sin);
+            let normal = normalize((radii.1 * cos, radii.0 * sin), (cos, sin));
+            let tangent = normalize(
+                (-radii.0 * sin * clockwise, radii.1 * cos * clockwise),
+                (-sin * clockwise, cos * clockwise),
+            );
+            (point, normal, tangent)
+        };
+
+        let angles = arc_angles(radii, self.start_angle, self.start_angle + sweep);
+        let mut outline: Vec<(f32, f32)> = angles
+            .iter()
+            .map(|&angle| {
+                let (point, normal, _) = frame(angle);
+                (point.0 + normal.0 * half, point.1 + normal.1 * half)
+            })
+            .collect();
+        let (end, _, end_tangent) = frame(self.start_angle + sweep);
+        outline.extend(self.cap.points(end, end_tangent, half));
+        outline.extend(angles.iter().rev().map(|&angle| {
+            let (point, normal, _) = frame(angle);
+            (point.0 - normal.0 * half, point.1 - normal.1 * half)
+        }));
+        let (start, _, start_tangent) = frame(self.start_angle);
+        let backwards = (-start_tangent.0, -start_tangent.1);
+        outline.extend(self.cap.points(start, backwards, half));
+        outline
+    }
+}
+
+/// Scales a vector to unit length, returning `fallback` for vectors without a length
+fn normalize(vector: (f32, f32), fallback: (f32, f32)) -> (f32, f32) {
+    let length = (vector.0 * vector.0 + vector.1 * vector.1).sqrt();
+    if length > 0.0 {
+        (vector.0 / length, vector.1 / length)
+    } else {
+        fallback
+    }
+}
+
+impl Drawable for Arc {
+    fn draw(&self, canvas: &mut Canvas) {
+        let finite = [
+            self.center.0,
+            self.center.1,
+            self.radii.0,
+            self.radii.1,
+            self.start_angle,
+            self.end_angle,
+            self.width,
+        ]
+        .iter()
+        .all(|c| c.is_finite());
+        if !finite || self.width <= 0.0 {
+            return;
+        }
+        let (mut center, mut radii) = (self.center, self.radii);
+        if canvas.hints.pixel_snap {
+            center = (center.0.round(), center.1.round());
+            radii = (radii.0.round(), radii.1.round());
+        }
+        let mut rasterizer = Rasterizer::new();
+        rasterizer.add_shape(&self.outline(center, radii));
+        let antialias = self.antialiased.unwrap_or(canvas.hints.antialias);
+        rasterizer.draw(canvas, &self.stroke, antialias);
+    }
+}
@@ -0,0 +1,50 @@
+use paint::Paint;
+use shapes::ellipse::Ellipse;
+use Canvas;
+use Drawable;
+
+/// A drawable object that represents a circle
+pub struct Circle {
+    /// Position of the center of the circle
+    pub center: (f32, f32),
+    /// The radius of the circle, the border will be contained within this radius
+    pub radius: f32,
+    /// The border that is drawn around the perimeter of the circle. It's arguments are thickness
+    /// of border and paint of border
+    pub border: Option<(f32, Paint)>,
+    /// The paint of the fill (area) of the circle
+    pub fill: Option<Paint>,
+    /// Decides whether the circle will be antialiased, the canvas' hints decide when `None`
+    pub antialiased: Option<bool>,
+}
+
+impl Circle {
+    /// Creates a new Circle object with a solid border and fill
+    pub fn new(
+        center: (f32, f32),
+        radius: f32,
+        border: Option<(f32, [u8; 4])>,
+        fill: Option<[u8; 4]>,
+    ) -> Circle {
+        Circle {
+            center,
+            radius,
+            border: border.map(|(thickness, color)| (thickness, Paint::Solid(color))),
+            fill: fill.map(Paint::Solid),
+            antialiased: None,
+        }
+    }
+}
+
+impl Drawable for Circle {
+    fn draw(&self, canvas: &mut Canvas) {
+        let ellipse = Ellipse {
+            center: self.center,
+            radii: (self.radius, self.radius),
+            border: self.border.clone(),
+            fill: self.fill.clone(),
+            antialiased: self.antialiased,
+        };
+        ellipse.draw(canvas);
+    }
+}
@@ -0,0 +1,72 @@
+use paint::Paint;
+use raster::{fill_with_border, Rasterizer};
+use Canvas;
+use Drawable;
+
+/// A drawable object that represents an axis-aligned ellipse
+pub struct Ellipse {
+    /// Position of the center of the ellipse
+    pub center: (f32, f32),
+    /// The horizontal and vertical radius of the ellipse, the border will be contained within
+    /// these radii
+    pub radii: (f32, f32),
+    /// The border that is drawn around the perimeter of the ellipse. It's arguments are thickness
+    /// of border and paint of border
+    pub border: Option<(f32, Paint)>,
+    /// The paint of the fill (area) of the ellipse
+    pub fill: Option<Paint>,
+    /// Decides whether the ellipse will be antialiased, the canvas' hints decide when `None`
+    pub antialiased: Option<bool>,
+}
+
+impl Ellipse {
+    /// Creates a new Ellipse object with a solid border and fill
+    pub fn new(
+        center: (f32, f32),
+        radii: (f32, f32),
+        border: Option<(f32, [u8; 4])>,
+        fill: Option<[u8; 4]>,
+    ) -> Ellipse {
+        Ellipse {
+            center,
+            radii,
+            border: border.map(|(thickness, color)| (thickness, Paint::Solid(color))),
+            fill: fill.map(Paint::Solid),
+            antialiased: None,
+        }
+    }
+}
+
+impl Drawable for Ellipse {
+    fn draw(&self, canvas: &mut Canvas) {
+        let (mut center, mut radii) = (self.center, (self.radii.0.abs(), self.radii.1.abs()));
+        if canvas.hints.pixel_snap {
+            center = (center.0.round(), center.1.round());
+            radii = (radii.0.round(), radii.1.round());
+        }
+        let thickness = self
+            .border
+            .as_ref()
+            .map(|border| border.0.max(0.0))
+            .unwrap_or(0.0);
+        let mut outer = Rasterizer::new();
+        outer.add_ellipse(center, radii);
+        let mut inner = Rasterizer::new();
+        inner.add_ellipse(
+            center,
+            (
+                (radii.0 - thickness).max(0.0),
+                (radii.1 - thickness).max(0.0),
+            ),
+        );
+        let antialias = self.antialiased.unwrap_or(canvas.hints.antialias);
+        fill_with_border(
+            canvas,
+            &outer,
+            &inner,
+            self.fill.as_ref(),
+            self.border.as_ref().map(|border| &border.1),
+            antialias,
+        );
+    }
+}
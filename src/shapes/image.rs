@@ -0,0 +1,387 @@
+use Canvas;
+use Drawable;
+
+/// The order of the channels of each pixel in the data of an image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PixelFormat {
+    /// Bytes in the order red, green, blue, alpha
+    Rgba,
+    /// Bytes in the order alpha, red, green, blue
+    Argb,
+}
+
+impl PixelFormat {
+    /// Returns the color of the pixel in the first four bytes of `pixel` in the canvas' ARGB
+    /// order
+    pub(crate) fn to_argb(self, pixel: &[u8]) -> [u8; 4] {
+        match self {
+            PixelFormat::Rgba => [pixel[3], pixel[0], pixel[1], pixel[2]],
+            PixelFormat::Argb => [pixel[0], pixel[1], pixel[2], pixel[3]],
+        }
+    }
+}
+
+/// Decides how the pixels of a scaled image are computed from the pixels of the source
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Filter {
+    /// Takes the source pixel nearest to each pixel, which keeps pixel art sharp
+    Nearest,
+    /// Interpolates between the four nearest source pixels, which is smooth when enlarging but
+    /// skips source pixels when shrinking to less than half the size
+    Bilinear,
+    /// Averages all the source pixels that each pixel covers, weighted by how much of them it
+    /// covers, which avoids aliasing when shrinking by any amount
+    Box,
+}
+
+/// A drawable object that represents an image, made of pixels with 8 bit channels and straight
+/// (not premultiplied) alpha
+#[derive(Clone)]
+pub struct Image<'a> {
+    /// The pixels of the image, row after row
+    pub data: &'a [u8],
+    /// The width of the image in pixels
+    pub width: usize,
+    /// The height of the image in pixels
+    pub height: usize,
+    /// The number of bytes between the starts of neighbouring rows
+    pub stride: usize,
+    /// The order of the channels of each pixel
+    pub format: PixelFormat,
+    /// Position on the canvas of the top-left corner of the drawn part of the image
+    pub pos: (usize, usize),
+    /// The part of the image that is drawn as its top-left corner and size, the whole image is
+    /// drawn when `None`
+    pub source: Option<((usize, usize), (usize, usize))>,
+    /// The opacity the image is drawn with, from 0.0 to 1.0
+    pub opacity: f32,
+    /// The size on the canvas that the drawn part of the image is scaled to, it is drawn at its
+    /// own size when `None`
+    pub size: Option<(usize, usize)>,
+    /// The filter used when the image is scaled
+    pub filter: Filter,
+}
+
+impl<'a> Image<'a> {
+    /// Creates a new Image object that draws the whole image at full opacity
+    pub fn new(
+        data: &'a [u8],
+        width: usize,
+        height: usize,
+        stride: usize,
+        format: PixelFormat,
+        pos: (usize, usize),
+    ) -> Image<'a> {
+        assert!(
+            stride >= width * 4,
+            "Incorrect Dimensions - Stride is less than four bytes per pixel"
+        );
+        assert!(
+            height == 0 || data.len() >= stride * (height - 1) + width * 4,
+            "Incorrect Dimensions - Data is too short for the image"
+        );
+        Image {
+            data,
+            width,
+            height,
+            stride,
+            format,
+            pos,
+            source: None,
+            opacity: 1.0,
+            size: None,
+            filter: Filter::Bilinear,
+        }
+    }
+
+    /// Returns the color of a pixel of the image in the canvas' ARGB order
+    pub(crate) fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        let base = y * self.stride + x * 4;
+        self.format.to_argb(&self.data[base..base + 4])
+    }
+
+    /// Returns the part of the image that is drawn, limited to the pixels that exist in its data
+    pub(crate) fn source_rect(&self) -> ((usize, usize), (usize, usize)) {
+        let mut height = self.height;
+        if height > 0 && self.data.len() < self.stride * (height - 1) + self.width * 4 {
+            height = (self.data.len() + self.stride - self.width * 4) / self.stride;
+        }
+        let ((x, y), (w, h)) = self.source.unwrap_or(((0, 0), (self.width, self.height)));
+        let x = x.min(self.width);
+        let y = y.min(height);
+        ((x, y), (w.min(self.width - x), h.min(height - y)))
+    }
+
+    /// Draws the source rectangle at the position on the canvas without scaling
+    fn draw_unscaled(&self, canvas: &mut Canvas, opacity: f32) {
+        let ((src_x, src_y), (width, height)) = self.source_rect();
+        let width = width.min(canvas.width.saturating_sub(self.pos.0));
+        let height = height.min(canvas.height.saturating_sub(self.pos.1));
+        let mut row = Vec::with_capacity(width);
+        for y in 0..height {
+            row.clear();
+            row.extend((0..width).map(|x| {
+                let mut color = self.pixel(src_x + x, src_y + y);
+                if opacity < 1.0 {
+                    color[0] = (f32::from(color[0]) * opacity).round() as u8;
+                }
+                color
+            }));
+            canvas.draw_row((self.pos.0, self.pos.1 + y), &row);
+        }
+    }
+
+    /// Draws the source rectangle scaled to `size`. Colors are filtered with premultiplied alpha,
+    /// so transparent pixels do not darken their neighbours, and only pixels inside of the source
+    /// rectangle are sampled
+    fn draw_scaled(&self, canvas: &mut Canvas, size: (usize, usize), opacity: f32) {
+        let ((src_x, src_y), (src_width, src_height)) = self.source_rect();
+        if src_width == 0 || src_height == 0 {
+            return;
+        }
+        let width = size.0.min(canvas.width.saturating_sub(self.pos.0));
+        let height = size.1.min(canvas.height.saturating_sub(self.pos.1));
+        if width == 0 || height == 0 {
+            return;
+        }
+        let columns = taps(self.filter, src_width, size.0, width);
+        let rows = taps(self.filter, src_height, size.1, height);
+
+        // Source rows are scaled horizontally once each, then combined for every row drawn
+        let mut scaled_rows: Vec<Option<Vec<[f32; 4]>>> = vec![None; src_height];
+        let mut row_colors = vec![[0.0f32; 4]; width];
+        for (y, row_taps) in rows.iter().enumerate() {
+            for color in &mut row_colors {
+                *color = [0.0; 4];
+            }
+            for &(sy, wy) in row_taps {
+                if scaled_rows[sy].is_none() {
+                    let scaled = columns
+                        .iter()
+                        .map(|column_taps| {
+                            let mut sum = [0.0f32; 4];
+                            for &(sx, wx) in column_taps {
+                                let pixel = self.pixel(src_x + sx, src_y + sy);
+                                let alpha = f32::from(pixel[0]) * wx;
+                                sum[0] += alpha;
+                                for c in 1..4 {
+                                    sum[c] += f32::from(pixel[c]) * alpha;
+                                }
+                            }
+                            sum
+                        })
+                        .collect();
+                    scaled_rows[sy] = Some(scaled);
+                }
+                if let Some(ref scaled) = scaled_rows[sy] {
+                    for (color, source) in row_colors.iter_mut().zip(scaled) {
+                        for c in 0..4 {
+                            color[c] += source[c] * wy;
+                        }
+                    }
+                }
+            }
+            for (x, sum) in row_colors.iter().enumerate() {
+                if sum[0] <= 0.0 {
+                    continue;
+                }
+                let mut color = [(sum[0] * opacity).round().min(255.0) as u8, 0, 0, 0];
+                for c in 1..4 {
+                    color[c] = (sum[c] / sum[0]).round().min(255.0) as u8;
+                }
+                if color[0] > 0 {
+                    canvas.draw_point(self.pos.0 + x, self.pos.1 + y, color);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the source pixels that each of the first `visible` of `dst_len` pixels is made of and
+/// their weights, for a source `src_len` pixels long
+fn taps(filter: Filter, src_len: usize, dst_len: usize, visible: usize) -> Vec<Vec<(usize, f32)>> {
+    let scale = src_len as f32 / dst_len as f32;
+    let last = src_len - 1;
+    (0..visible)
+        .map(|d| match filter {
+            Filter::Nearest => {
+                let s = ((d as f32 + 0.5) * scale) as usize;
+                vec![(s.min(last), 1.0)]
+            }
+            Filter::Bilinear => {
+                let s = ((d as f32 + 0.5) * scale - 0.5).max(0.0);
+                let i = (s.floor() as usize).min(last);
+                let t = s - i as f32;
+                if i < last && t > 0.0 {
+                    vec![(i, 1.0 - t), (i + 1, t)]
+                } else {
+                    vec![(i, 1.0)]
+                }
+            }
+            Filter::Box => {
+                let (start, end) = (d as f32 * scale, (d + 1) as f32 * scale);
+                let mut taps = Vec::new();
+                let mut i = start.floor() as usize;
+                while (i as f32) < end && i <= last {
+                    let overlap = end.min(i as f32 + 1.0) - start.max(i as f32);
+                    if overlap > 0.0 {
+                        taps.push((i, overlap / scale));
+                    }
+                    i += 1;
+                }
+                taps
+            }
+        })
+        .collect()
+}
+
+impl<'a> Drawable for Image<'a> {
+    fn draw(&self, canvas: &mut Canvas) {
+        let opacity = self.opacity.max(0.0).min(1.0);
+        if opacity.is_nan() || opacity == 0.0 {
+            return;
+        }
+        let (_, source_size) = self.source_rect();
+        match self.size {
+            Some(size) if size != source_size => self.draw_scaled(canvas, size, opacity),
+            _ => self.draw_unscaled(canvas, opacity),
+        }
+    }
+}
+
+/// Decides how the edges and the center of a nine-slice image fill their part of it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SliceMode {
+    /// The slice is scaled to fill its part
+    Stretch,
+    /// The slice is repeated at its own size to fill its part, the last repeat is cut off
+    Tile,
+}
+
+/// A drawable object that draws an image cut into nine slices by four insets. The corners are
+/// drawn at their own size, the top and bottom edges fill the width between them, the left and
+/// right edges fill the height between them and the center fills the rest. Corners are scaled
+/// down evenly when they do not fit
+pub struct NineSlice<'a> {
+    /// The image that is sliced, its source rectangle, opacity and filter are used while its
+    /// position and size are ignored
+    pub image: Image<'a>,
+    /// The distance of the slicing lines from the top, right, bottom and left of the source
+    /// rectangle of the image
+    pub insets: (usize, usize, usize, usize),
+    /// Position of the top-left corner of the drawn image
+    pub pos: (usize, usize),
+    /// The size of the drawn image
+    pub size: (usize, usize),
+    /// How the edges fill their parts
+    pub edges: SliceMode,
+    /// How the center fills its part, the center is not drawn when `None`
+    pub center: Option<SliceMode>,
+}
+
+impl<'a> NineSlice<'a> {
+    /// Creates a new NineSlice object with stretched edges and center
+    pub fn new(
+        image: Image<'a>,
+        insets: (usize, usize, usize, usize),
+        pos: (usize, usize),
+        size: (usize, usize),
+    ) -> NineSlice<'a> {
+        NineSlice {
+            image,
+            insets,
+            pos,
+            size,
+            edges: SliceMode::Stretch,
+            center: Some(SliceMode::Stretch),
+        }
+    }
+}
+
+/// Splits a source span and a destination span into their start, middle and end parts as
+/// `(source start, source length, destination start, destination length)`
+fn slices(
+    src: (usize, usize),
+    dst: (usize, usize),
+    insets: (usize, usize),
+) -> [(usize, usize, usize, usize); 3] {
+    let (src_start, src_len) = src;
+    let (dst_start, dst_len) = dst;
+    let start = insets.0.min(src_len);
+    let end = insets.1.min(src_len - start);
+    // Corners that do not fit into the destination are scaled down evenly
+    let (dst_first, dst_last) = if start + end > dst_len {
+        let first = start * dst_len / (start + end);
+        (first, dst_len - first)
+    } else {
+        (start, end)
+    };
+    [
+        (src_start, start, dst_start, dst_first),
+        (
+            src_start + start,
+            src_len - start - end,
+            dst_start + dst_first,
+            dst_len - dst_first - dst_last,
+        ),
+        (
+            src_start + src_len - end,
+            end,
+            dst_start + dst_len - dst_last,
+            dst_last,
+        ),
+    ]
+}
+
+/// Returns the source and destination spans of the tiles that fill a destination span
+fn tiles(slice: (usize, usize, usize, usize), tile: bool) -> Vec<(usize, usize, usize, usize)> {
+    let (src_start, src_len, dst_start, dst_len) = slice;
+    if !tile || src_len == 0 {
+        return vec![slice];
+    }
+    (0..dst_len)
+        .step_by(src_len)
+        .map(|offset| {
+            let len = src_len.min(dst_len - offset);
+            (src_start, len, dst_start + offset, len)
+        })
+        .collect()
+}
+
+impl<'a> Drawable for NineSlice<'a> {
+    fn draw(&self, canvas: &mut Canvas) {
+        let ((src_x, src_y), (src_width, src_height)) = self.image.source_rect();
+        let (top, right, bottom, left) = self.insets;
+        let columns = slices((src_x, src_width), (self.pos.0, self.size.0), (left, right));
+        let rows = slices(
+            (src_y, src_height),
+            (self.pos.1, self.size.1),
+            (top, bottom),
+        );
+        for (i, &row) in rows.iter().enumerate() {
+            for (j, &column) in columns.iter().enumerate() {
+                let mode = if i == 1 && j == 1 {
+                    match self.center {
+                        Some(mode) => mode,
+                        None => continue,
+                    }
+                } else {
+                    self.edges
+                };
+                let tile = mode == SliceMode::Tile;
+                for &(sy, sh, dy, dh) in &tiles(row, tile && i == 1) {
+                    for &(sx, sw, dx, dw) in &tiles(column, tile && j == 1) {
+                        if sw == 0 || sh == 0 || dw == 0 || dh == 0 {
+                            continue;
+                        }
+                        let mut part = self.image.clone();
+                        part.source = Some(((sx, sy), (sw, sh)));
+                        part.pos = (dx, dy);
+                        part.size = Some((dw, dh));
+                        part.draw(canvas);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,2 +1,22 @@
+/// A module that contains functions and objects relating to arcs
+pub mod arc;
+/// A module that contains functions and objects relating to circles
+pub mod circle;
+/// A module that contains functions and objects relating to ellipses
+pub mod ellipse;
+/// A module that contains functions and objects relating to images
+pub mod image;
+/// A module that contains functions and objects relating to paths
+pub mod path;
+/// A module that contains functions and objects relating to pies
+pub mod pie;
+/// A module that contains functions and objects relating to polygons
+pub mod polygon;
 /// A module that contains functions and objects relating to rectangles
 pub mod rectangle;
+/// A module that contains functions and objects relating to regular polygons
+pub mod regular_polygon;
+/// A module that contains functions and objects relating to stars
+pub mod star;
+/// A module that contains functions and objects relating to triangles
+pub mod triangle;
@@ -0,0 +1,344 @@
+/// A module that contains functions and objects relating to SVG path data
+pub mod svg;
+
+use std::f32::consts::PI;
+
+use line::Cap;
+use paint::Paint;
+use raster::{arc_angles, Rasterizer};
+use shapes::polygon::FillRule;
+use Canvas;
+use Drawable;
+
+/// The largest distance, in pixels, that flattened curves may stray from the true curve
+const TOLERANCE: f32 = 0.1;
+
+/// A single command of a path
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Segment {
+    /// Starts a new subpath at a point
+    MoveTo((f32, f32)),
+    /// Draws a straight line to a point
+    LineTo((f32, f32)),
+    /// Draws a quadratic Bezier curve through a control point to a point
+    QuadTo((f32, f32), (f32, f32)),
+    /// Draws a cubic Bezier curve through two control points to a point
+    CubicTo((f32, f32), (f32, f32), (f32, f32)),
+    /// Draws an elliptical arc to a point, the same way as SVG's arc command
+    ArcTo {
+        /// The horizontal and vertical radius of the ellipse
+        radii: (f32, f32),
+        /// The rotation of the ellipse's x axis in radians
+        x_rotation: f32,
+        /// Decides whether the longer of the two possible arcs is drawn
+        large_arc: bool,
+        /// Decides whether the arc is drawn in the clockwise direction
+        sweep: bool,
+        /// The point the arc ends at
+        to: (f32, f32),
+    },
+    /// Closes the subpath with a straight line back to its start
+    Close,
+}
+
+/// A drawable object that represents an outline made of lines and curves
+pub struct Path {
+    /// The commands that make up the path
+    pub segments: Vec<Segment>,
+    /// The stroke that is drawn along the path, centered on it. It's arguments are width of
+    /// stroke and paint of stroke
+    pub stroke: Option<(f32, Paint)>,
+    /// The paint of the fill (area) of the path, every subpath is closed when filling
+    pub fill: Option<Paint>,
+    /// The rule that decides which parts of the path are filled
+    pub fill_rule: FillRule,
+    /// The shape of the ends of subpaths that are not closed
+    pub cap: Cap,
+    /// Decides whether the path will be antialiased, the canvas' hints decide when `None`
+    pub antialiased: Option<bool>,
+}
+
+impl Path {
+    /// Creates a new Path object without any segments, fill or stroke
+    pub fn new() -> Path {
+        Path {
+            segments: Vec::new(),
+            stroke: None,
+            fill: None,
+            fill_rule: FillRule::NonZero,
+            cap: Cap::Butt,
+            antialiased: None,
+        }
+    }
+
+    /// Creates a new Path object from SVG path data, such as the `d` attribute of a `<path>`
+    /// element, without any fill or stroke
+    pub fn from_svg(data: &str) -> Result<Path, svg::ParseError> {
+        let mut path = Path::new();
+        path.segments = svg::parse(data)?;
+        Ok(path)
+    }
+
+    /// Starts a new subpath at a point
+    pub fn move_to(&mut self, to: (f32, f32)) -> &mut Path {
+        self.segments.push(Segment::MoveTo(to));
+        self
+    }
+
+    /// Draws a straight line to a point
+    pub fn line_to(&mut self, to: (f32, f32)) -> &mut Path {
+        self.segments.push(Segment::LineTo(to));
+        self
+    }
+
+    /// Draws a quadratic Bezier curve through a control point to a point
+    pub fn quad_to(&mut self, control: (f32, f32), to: (f32, f32)) -> &mut Path {
+        self.segments.push(Segment::QuadTo(control, to));
+        self
+    }
+
+    /// Draws a cubic Bezier curve through two control points to a point
+    pub fn cubic_to(
+        &mut self,
+        control1: (f32, f32),
+        control2: (f32, f32),
+        to: (f32, f32),
+    ) -> &mut Path {
+        self.segments.push(Segment::CubicTo(control1, control2, to));
+        self
+    }
+
+    /// Draws an elliptical arc to a point, the same way as SVG's arc command
+    pub fn arc_to(
+        &mut self,
+        radii: (f32, f32),
+        x_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: (f32, f32),
+    ) -> &mut Path {
+        self.segments.push(Segment::ArcTo {
+            radii,
+            x_rotation,
+            large_arc,
+            sweep,
+            to,
+        });
+        self
+    }
+
+    /// Closes the current subpath with a straight line back to its start
+    pub fn close(&mut self) -> &mut Path {
+        self.segments.push(Segment::Close);
+        self
+    }
+
+    /// Flattens the path into lines, returning the points of every subpath and whether it is
+    /// closed
+    pub(crate) fn flatten(&self) -> Vec<(Vec<(f32, f32)>, bool)> {
+        let mut subpaths = Vec::new();
+        let mut points: Vec<(f32, f32)> = Vec::new();
+        let mut current = (0.0, 0.0);
+        let mut start = (0.0, 0.0);
+        for segment in &self.segments {
+            if points.is_empty() {
+                points.push(current);
+            }
+            match *segment {
+                Segment::MoveTo(to) => {
+                    if points.len() > 1 {
+                        subpaths.push((points.clone(), false));
+                    }
+                    points.clear();
+                    points.push(to);
+                    start = to;
+                    current = to;
+                }
+                Segment::LineTo(to) => {
+                    points.push(to);
+                    current = to;
+                }
+                Segment::QuadTo(control, to) => {
+                    flatten_quad(&mut points, current, control, to);
+                    current = to;
+                }
+                Segment::CubicTo(control1, control2, to) => {
+                    flatten_cubic(&mut points, current, control1, control2, to);
+                    current = to;
+                }
+                Segment::ArcTo {
+                    radii,
+                    x_rotation,
+                    large_arc,
+                    sweep,
+                    to,
+                } => {
+                    flatten_arc(
+                        &mut points,
+                        current,
+                        radii,
+                        x_rotation,
+                        large_arc,
+                        sweep,
+                        to,
+                    );
+                    current = to;
+                }
+                Segment::Close => {
+                    subpaths.push((points.clone(), true));
+                    points.clear();
+                    current = start;
+                }
+            }
+        }
+        if points.len() > 1 {
+            subpaths.push((points, false));
+        }
+        subpaths
+    }
+}
+
+impl Default for Path {
+    fn default() -> Path {
+        Path::new()
+    }
+}
+
+fn flatten_quad(points: &mut Vec<(f32, f32)>, p0: (f32, f32), p1: (f32, f32), p2: (f32, f32)) {
+    // The distance between the curve and a chord of a parameter range h is at most
+    // |p0 - 2p1 + p2| * h^2 / 4
+    let dd = ((p0.0 - 2.0 * p1.0 + p2.0).powi(2) + (p0.1 - 2.0 * p1.1 + p2.1).powi(2)).sqrt();
+    let segments = segment_count((dd / (4.0 * TOLERANCE)).sqrt());
+    for i in 1..=segments {
+        let t = i as f32 / segments as f32;
+        let mt = 1.0 - t;
+        points.push((
+            mt * mt * p0.0 + 2.0 * mt * t * p1.0 + t * t * p2.0,
+            mt * mt * p0.1 + 2.0 * mt * t * p1.1 + t * t * p2.1,
+        ));
+    }
+}
+
+fn flatten_cubic(
+    points: &mut Vec<(f32, f32)>,
+    p0: (f32, f32),
+    p1: (f32, f32),
+    p2: (f32, f32),
+    p3: (f32, f32),
+) {
+    // The distance between the curve and a chord of a parameter range h is at most
+    // 3 * max(|p0 - 2p1 + p2|, |p1 - 2p2 + p3|) * h^2 / 4
+    let dd1 = ((p0.0 - 2.0 * p1.0 + p2.0).powi(2) + (p0.1 - 2.0 * p1.1 + p2.1).powi(2)).sqrt();
+    let dd2 = ((p1.0 - 2.0 * p2.0 + p3.0).powi(2) + (p1.1 - 2.0 * p2.1 + p3.1).powi(2)).sqrt();
+    let segments = segment_count((3.0 * dd1.max(dd2) / (4.0 * TOLERANCE)).sqrt());
+    for i in 1..=segments {
+        let t = i as f32 / segments as f32;
+        let mt = 1.0 - t;
+        let (a, b, c, d) = (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+        points.push((
+            a * p0.0 + b * p1.0 + c * p2.0 + d * p3.0,
+            a * p0.1 + b * p1.1 + c * p2.1 + d * p3.1,
+        ));
+    }
+}
+
+/// Flattens an arc given by its end points, converting it to a center and angles as described in
+/// the SVG specification
+fn flatten_arc(
+    points: &mut Vec<(f32, f32)>,
+    from: (f32, f32),
+    radii: (f32, f32),
+    x_rotation: f32,
+    large_arc: bool,
+    sweep: bool,
+    to: (f32, f32),
+) {
+    let (mut rx, mut ry) = (radii.0.abs(), radii.1.abs());
+    if from == to {
+        return;
+    }
+    if rx == 0.0 || ry == 0.0 {
+        points.push(to);
+        return;
+    }
+    let (sin, cos) = x_rotation.sin_cos();
+    let (hx, hy) = ((from.0 - to.0) / 2.0, (from.1 - to.1) / 2.0);
+    let x1 = cos * hx + sin * hy;
+    let y1 = -sin * hx + cos * hy;
+    // Radii that are too small to reach the end point are scaled up until they just do
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+    let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let mut factor = (numerator / denominator).max(0.0).sqrt();
+    if large_arc == sweep {
+        factor = -factor;
+    }
+    let cx1 = factor * rx * y1 / ry;
+    let cy1 = -factor * ry * x1 / rx;
+    let center = (
+        cos * cx1 - sin * cy1 + (from.0 + to.0) / 2.0,
+        sin * cx1 + cos * cy1 + (from.1 + to.1) / 2.0,
+    );
+
+    let start = ((y1 - cy1) / ry).atan2((x1 - cx1) / rx);
+    let end = ((-y1 - cy1) / ry).atan2((-x1 - cx1) / rx);
+    let mut delta = end - start;
+    if sweep && delta < 0.0 {
+        delta += 2.0 * PI;
+    } else if !sweep && delta > 0.0 {
+        delta -= 2.0 * PI;
+    }
+
+    let angles = arc_angles((rx, ry), start, start + delta);
+    for &angle in angles.iter().skip(1) {
+        let (x, y) = (rx * angle.cos(), ry * angle.sin());
+        points.push((center.0 + cos * x - sin * y, center.1 + sin * x + cos * y));
+    }
+    // Make sure the arc ends exactly where it was asked to
+    if let Some(last) = points.last_mut() {
+        *last = to;
+    }
+}
+
+fn segment_count(segments: f32) -> usize {
+    if segments.is_finite() {
+        (segments.ceil() as usize).max(1).min(1000)
+    } else {
+        1
+    }
+}
+
+impl Drawable for Path {
+    fn draw(&self, canvas: &mut Canvas) {
+        let mut subpaths = self.flatten();
+        if canvas.hints.pixel_snap {
+            for (points, _) in &mut subpaths {
+                for point in points.iter_mut() {
+                    *point = (point.0.round(), point.1.round());
+                }
+            }
+        }
+        let antialias = self.antialiased.unwrap_or(canvas.hints.antialias);
+        if let Some(ref fill) = self.fill {
+            let mut rasterizer = Rasterizer::new();
+            rasterizer.set_fill_rule(self.fill_rule);
+            for (points, _) in &subpaths {
+                rasterizer.add_polygon(points);
+            }
+            rasterizer.draw(canvas, fill, antialias);
+        }
+        if let Some((width, ref paint)) = self.stroke {
+            if width > 0.0 {
+                let mut rasterizer = Rasterizer::new();
+                for (points, closed) in &subpaths {
+                    rasterizer.add_stroke(points, *closed, width, self.cap);
+                }
+                rasterizer.draw(canvas, paint, antialias);
+            }
+        }
+    }
+}
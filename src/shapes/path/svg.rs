@@ -0,0 +1,385 @@
+use std::error::Error;
+use std::fmt;
+
+use shapes::path::Segment;
+
+/// Describes what went wrong while parsing SVG path data
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseErrorKind {
+    /// The path data does not start with a move command
+    MissingMoveTo,
+    /// A character that cannot appear at this position
+    UnexpectedCharacter(char),
+    /// The path data ends in the middle of a command
+    UnexpectedEnd,
+    /// A number is malformed or out of range
+    InvalidNumber,
+    /// An arc flag is neither `0` nor `1`
+    InvalidFlag,
+}
+
+/// An error found while parsing SVG path data
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParseError {
+    /// The byte offset in the path data at which the error was found
+    pub position: usize,
+    /// What went wrong
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ParseErrorKind::MissingMoveTo => write!(f, "path data must start with a move command"),
+            ParseErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character {:?}", c),
+            ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of path data"),
+            ParseErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ParseErrorKind::InvalidFlag => write!(f, "arc flags must be 0 or 1"),
+        }?;
+        write!(f, " at byte {}", self.position)
+    }
+}
+
+impl Error for ParseError {}
+
+struct Parser<'a> {
+    data: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.data.as_bytes().get(self.pos).cloned()
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            position: self.pos,
+            kind,
+        }
+    }
+
+    /// Returns an error describing whatever is found at the current position
+    fn unexpected(&self) -> ParseError {
+        match self.data[self.pos..].chars().next() {
+            Some(c) => self.error(ParseErrorKind::UnexpectedCharacter(c)),
+            None => self.error(ParseErrorKind::UnexpectedEnd),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') | Some(b'\x0C') = self.peek()
+        {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips whitespace with at most one comma in it
+    fn skip_separator(&mut self) {
+        self.skip_whitespace();
+        if self.peek() == Some(b',') {
+            self.pos += 1;
+            self.skip_whitespace();
+        }
+    }
+
+    /// Returns whether a number starts at the current position
+    fn at_number(&self) -> bool {
+        match self.peek() {
+            Some(b'0'..=b'9') | Some(b'-') | Some(b'+') | Some(b'.') => true,
+            _ => false,
+        }
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.pos;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    fn number(&mut self) -> Result<f32, ParseError> {
+        self.skip_separator();
+        let start = self.pos;
+        if let Some(b'-') | Some(b'+') = self.peek() {
+            self.pos += 1;
+        }
+        let mut digits = self.skip_digits();
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            digits += self.skip_digits();
+        }
+        if digits == 0 {
+            self.pos = start;
+            return Err(self.unexpected());
+        }
+        if let Some(b'e') | Some(b'E') = self.peek() {
+            let mantissa_end = self.pos;
+            self.pos += 1;
+            if let Some(b'-') | Some(b'+') = self.peek() {
+                self.pos += 1;
+            }
+            if self.skip_digits() == 0 {
+                // The exponent is not part of the number after all
+                self.pos = mantissa_end;
+            }
+        }
+        match self.data[start..self.pos].parse::<f32>() {
+            Ok(number) if number.is_finite() => Ok(number),
+            _ => Err(ParseError {
+                position: start,
+                kind: ParseErrorKind::InvalidNumber,
+            }),
+        }
+    }
+
+    fn point(&mut self) -> Result<(f32, f32), ParseError> {
+        let x = self.number()?;
+        let y = self.number()?;
+        Ok((x, y))
+    }
+
+    fn flag(&mut self) -> Result<bool, ParseError> {
+        self.skip_separator();
+        match self.peek() {
+            Some(b'0') => {
+                self.pos += 1;
+                Ok(false)
+            }
+            Some(b'1') => {
+                self.pos += 1;
+                Ok(true)
+            }
+            Some(_) => Err(self.error(ParseErrorKind::InvalidFlag)),
+            None => Err(self.error(ParseErrorKind::UnexpectedEnd)),
+        }
+    }
+}
+
+/// Parses SVG path data into absolute path segments. Relative commands are resolved against the
+/// current point and smooth curves are given their reflected control points
+pub fn parse(data: &str) -> Result<Vec<Segment>, ParseError> {
+    let mut parser = Parser { data, pos: 0 };
+    let mut segments = Vec::new();
+    let mut current = (0.0, 0.0);
+    let mut start = (0.0, 0.0);
+    // The last control point of the previous segment, if it was a cubic or a quadratic curve
+    let mut last_cubic: Option<(f32, f32)> = None;
+    let mut last_quad: Option<(f32, f32)> = None;
+    let mut command: Option<u8> = None;
+
+    loop {
+        parser.skip_whitespace();
+        let next = match parser.peek() {
+            Some(next) => next,
+            None => break,
+        };
+        // Numbers without a command repeat the previous command, a move turns into a line
+        let (name, command_pos) = if next.is_ascii_alphabetic() {
+            parser.pos += 1;
+            (next, parser.pos - 1)
+        } else if let (Some(previous), true) = (command, parser.at_number()) {
+            parser.skip_separator();
+            match previous {
+                b'M' => (b'L', parser.pos),
+                b'm' => (b'l', parser.pos),
+                b'Z' | b'z' => return Err(parser.unexpected()),
+                previous => (previous, parser.pos),
+            }
+        } else if command.is_none() && (parser.at_number() || next == b',') {
+            return Err(parser.error(ParseErrorKind::MissingMoveTo));
+        } else {
+            return Err(parser.unexpected());
+        };
+        if command.is_none() && name != b'M' && name != b'm' {
+            return Err(ParseError {
+                position: command_pos,
+                kind: ParseErrorKind::MissingMoveTo,
+            });
+        }
+
+        let relative = name.is_ascii_lowercase();
+        let offset = |p: (f32, f32)| {
+            if relative {
+                (current.0 + p.0, current.1 + p.1)
+            } else {
+                p
+            }
+        };
+        let reflect = |control: Option<(f32, f32)>| match control {
+            Some(c) => (2.0 * current.0 - c.0, 2.0 * current.1 - c.1),
+            None => current,
+        };
+        let (mut next_cubic, mut next_quad) = (None, None);
+        let segment = match name.to_ascii_uppercase() {
+            b'M' => {
+                let to = offset(parser.point()?);
+                start = to;
+                Segment::MoveTo(to)
+            }
+            b'L' => Segment::LineTo(offset(parser.point()?)),
+            b'H' => {
+                let x = parser.number()?;
+                Segment::LineTo((if relative { current.0 + x } else { x }, current.1))
+            }
+            b'V' => {
+                let y = parser.number()?;
+                Segment::LineTo((current.0, if relative { current.1 + y } else { y }))
+            }
+            b'C' => {
+                let control1 = offset(parser.point()?);
+                let control2 = offset(parser.point()?);
+                next_cubic = Some(control2);
+                Segment::CubicTo(control1, control2, offset(parser.point()?))
+            }
+            b'S' => {
+                let control1 = reflect(last_cubic);
+                let control2 = offset(parser.point()?);
+                next_cubic = Some(control2);
+                Segment::CubicTo(control1, control2, offset(parser.point()?))
+            }
+            b'Q' => {
+                let control = offset(parser.point()?);
+                next_quad = Some(control);
+                Segment::QuadTo(control, offset(parser.point()?))
+            }
+            b'T' => {
+                let control = reflect(last_quad);
+                next_quad = Some(control);
+                Segment::QuadTo(control, offset(parser.point()?))
+            }
+            b'A' => {
+                let rx = parser.number()?;
+                let ry = parser.number()?;
+                let x_rotation = parser.number()?.to_radians();
+                let large_arc = parser.flag()?;
+                let sweep = parser.flag()?;
+                Segment::ArcTo {
+                    radii: (rx, ry),
+                    x_rotation,
+                    large_arc,
+                    sweep,
+                    to: offset(parser.point()?),
+                }
+            }
+            b'Z' => Segment::Close,
+            _ => {
+                return Err(ParseError {
+                    position: command_pos,
+                    kind: ParseErrorKind::UnexpectedCharacter(char::from(name)),
+                })
+            }
+        };
+
+        current = match segment {
+            Segment::MoveTo(to)
+            | Segment::LineTo(to)
+            | Segment::QuadTo(_, to)
+            | Segment::CubicTo(_, _, to)
+            | Segment::ArcTo { to, .. } => to,
+            Segment::Close => start,
+        };
+        last_cubic = next_cubic;
+        last_quad = next_quad;
+        command = Some(name);
+        segments.push(segment);
+        parser.skip_separator();
+    }
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(data: &str) -> (usize, ParseErrorKind) {
+        let error = parse(data).unwrap_err();
+        (error.position, error.kind)
+    }
+
+    #[test]
+    fn numbers_after_move_repeat_as_lines() {
+        assert_eq!(
+            parse("M1 2 3 4 5,6").unwrap(),
+            vec![
+                Segment::MoveTo((1.0, 2.0)),
+                Segment::LineTo((3.0, 4.0)),
+                Segment::LineTo((5.0, 6.0)),
+            ]
+        );
+        assert_eq!(
+            parse("m1 2 3 4").unwrap(),
+            vec![Segment::MoveTo((1.0, 2.0)), Segment::LineTo((4.0, 6.0))]
+        );
+    }
+
+    #[test]
+    fn arc_flags_need_no_separators() {
+        assert_eq!(
+            parse("M0 0 a1 1 0 0110 10").unwrap()[1],
+            Segment::ArcTo {
+                radii: (1.0, 1.0),
+                x_rotation: 0.0,
+                large_arc: false,
+                sweep: true,
+                to: (10.0, 10.0),
+            }
+        );
+    }
+
+    #[test]
+    fn exponent_without_digits_is_not_part_of_the_number() {
+        assert_eq!(
+            parse("M1e2 1E-1").unwrap(),
+            vec![Segment::MoveTo((100.0, 0.1))]
+        );
+        assert_eq!(
+            error("M1 2e"),
+            (4, ParseErrorKind::UnexpectedCharacter('e'))
+        );
+        assert_eq!(
+            error("M1 2e+"),
+            (4, ParseErrorKind::UnexpectedCharacter('e'))
+        );
+    }
+
+    #[test]
+    fn relative_commands_after_close_start_at_the_subpath_start() {
+        assert_eq!(
+            parse("M10 10 l5 0 z l0 5").unwrap(),
+            vec![
+                Segment::MoveTo((10.0, 10.0)),
+                Segment::LineTo((15.0, 10.0)),
+                Segment::Close,
+                Segment::LineTo((10.0, 15.0)),
+            ]
+        );
+        assert_eq!(
+            parse("m1 1 h2 z m1 1").unwrap()[3],
+            Segment::MoveTo((2.0, 2.0))
+        );
+    }
+
+    #[test]
+    fn errors_point_at_their_position() {
+        assert_eq!(error("L1 2"), (0, ParseErrorKind::MissingMoveTo));
+        assert_eq!(error("  5 5"), (2, ParseErrorKind::MissingMoveTo));
+        assert_eq!(
+            error("M1 2 X3 4"),
+            (5, ParseErrorKind::UnexpectedCharacter('X'))
+        );
+        assert_eq!(error("M1 #"), (3, ParseErrorKind::UnexpectedCharacter('#')));
+        assert_eq!(
+            error("M1 2 z 3"),
+            (7, ParseErrorKind::UnexpectedCharacter('3'))
+        );
+        assert_eq!(error("M1"), (2, ParseErrorKind::UnexpectedEnd));
+        assert_eq!(error("M1 1e39"), (3, ParseErrorKind::InvalidNumber));
+        assert_eq!(
+            error("M0 0 A1 1 0 2 0 5 5"),
+            (12, ParseErrorKind::InvalidFlag)
+        );
+        assert_eq!(error("M0 0 A1 1 0 1"), (13, ParseErrorKind::UnexpectedEnd));
+    }
+}
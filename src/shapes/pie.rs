@@ -0,0 +1,84 @@
+use std::f32::consts::PI;
+
+use paint::Paint;
+use raster::{arc_points, Rasterizer};
+use Canvas;
+use Drawable;
+
+/// A drawable object that represents a filled wedge of an axis-aligned ellipse. Angles are in
+/// radians, start at the positive x axis and increase clockwise
+pub struct Pie {
+    /// Position of the center of the ellipse the wedge is cut from
+    pub center: (f32, f32),
+    /// The horizontal and vertical radius of the ellipse the wedge is cut from
+    pub radii: (f32, f32),
+    /// The angle the wedge starts at
+    pub start_angle: f32,
+    /// The angle the wedge ends at
+    pub end_angle: f32,
+    /// The paint of the fill of the wedge
+    pub fill: Paint,
+    /// Decides whether the wedge will be antialiased, the canvas' hints decide when `None`
+    pub antialiased: Option<bool>,
+}
+
+impl Pie {
+    /// Creates a new Pie object with a solid color
+    pub fn new(
+        center: (f32, f32),
+        radii: (f32, f32),
+        start_angle: f32,
+        end_angle: f32,
+        color: [u8; 4],
+    ) -> Pie {
+        Pie {
+            center,
+            radii,
+            start_angle,
+            end_angle,
+            fill: Paint::Solid(color),
+            antialiased: None,
+        }
+    }
+}
+
+impl Drawable for Pie {
+    fn draw(&self, canvas: &mut Canvas) {
+        let finite = [
+            self.center.0,
+            self.center.1,
+            self.radii.0,
+            self.radii.1,
+            self.start_angle,
+            self.end_angle,
+        ]
+        .iter()
+        .all(|c| c.is_finite());
+        if !finite {
+            return;
+        }
+        let (mut center, mut radii) = (self.center, self.radii);
+        if canvas.hints.pixel_snap {
+            center = (center.0.round(), center.1.round());
+            radii = (radii.0.round(), radii.1.round());
+        }
+        let sweep = (self.end_angle - self.start_angle)
+            .max(-2.0 * PI)
+            .min(2.0 * PI);
+        let mut rasterizer = Rasterizer::new();
+        if sweep.abs() >= 2.0 * PI {
+            rasterizer.add_ellipse(center, radii);
+        } else {
+            let mut outline = vec![center];
+            outline.extend(arc_points(
+                center,
+                radii,
+                self.start_angle,
+                self.start_angle + sweep,
+            ));
+            rasterizer.add_shape(&outline);
+        }
+        let antialias = self.antialiased.unwrap_or(canvas.hints.antialias);
+        rasterizer.draw(canvas, &self.fill, antialias);
+    }
+}
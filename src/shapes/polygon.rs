@@ -0,0 +1,74 @@
+use line::Cap;
+use paint::Paint;
+use raster::Rasterizer;
+use Canvas;
+use Drawable;
+
+/// Decides which parts of a self-intersecting or overlapping outline are inside of it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FillRule {
+    /// Areas that the outline winds around a non-zero number of times are filled
+    NonZero,
+    /// Areas that the outline winds around an odd number of times are filled
+    EvenOdd,
+}
+
+/// A drawable object that represents a closed polygon, which may be concave or self-intersecting
+pub struct Polygon {
+    /// The corners of the polygon, the last corner is joined back to the first
+    pub points: Vec<(f32, f32)>,
+    /// The stroke that is drawn along the edges of the polygon, centered on them. It's arguments
+    /// are width of stroke and paint of stroke
+    pub stroke: Option<(f32, Paint)>,
+    /// The paint of the fill (area) of the polygon
+    pub fill: Option<Paint>,
+    /// The rule that decides which parts of the polygon are filled
+    pub fill_rule: FillRule,
+    /// Decides whether the polygon will be antialiased, the canvas' hints decide when `None`
+    pub antialiased: Option<bool>,
+}
+
+impl Polygon {
+    /// Creates a new Polygon object with a solid stroke and fill
+    pub fn new(
+        points: Vec<(f32, f32)>,
+        stroke: Option<(f32, [u8; 4])>,
+        fill: Option<[u8; 4]>,
+        fill_rule: FillRule,
+    ) -> Polygon {
+        Polygon {
+            points,
+            stroke: stroke.map(|(width, color)| (width, Paint::Solid(color))),
+            fill: fill.map(Paint::Solid),
+            fill_rule,
+            antialiased: None,
+        }
+    }
+}
+
+impl Drawable for Polygon {
+    fn draw(&self, canvas: &mut Canvas) {
+        let points: Vec<(f32, f32)> = if canvas.hints.pixel_snap {
+            self.points
+                .iter()
+                .map(|p| (p.0.round(), p.1.round()))
+                .collect()
+        } else {
+            self.points.clone()
+        };
+        let antialias = self.antialiased.unwrap_or(canvas.hints.antialias);
+        if let Some(ref fill) = self.fill {
+            let mut rasterizer = Rasterizer::new();
+            rasterizer.set_fill_rule(self.fill_rule);
+            rasterizer.add_polygon(&points);
+            rasterizer.draw(canvas, fill, antialias);
+        }
+        if let Some((width, ref paint)) = self.stroke {
+            if width > 0.0 {
+                let mut rasterizer = Rasterizer::new();
+                rasterizer.add_stroke(&points, true, width, Cap::Butt);
+                rasterizer.draw(canvas, paint, antialias);
+            }
+        }
+    }
+}
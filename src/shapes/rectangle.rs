@@ -1,5 +1,8 @@
-use std::cmp::min;
+use std::f32::consts::PI;
 
+use blur::gaussian_blur;
+use paint::Paint;
+use raster::{arc_angles, fill_layers, PixelArea, Rasterizer};
 use Canvas;
 use Drawable;
 
@@ -19,148 +22,822 @@ bitflags! {
     }
 }
 
+/// The rounding radii of the corners of a rectangle
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Radii {
+    /// The radius of the top-left corner
+    pub top_left: f32,
+    /// The radius of the top-right corner
+    pub top_right: f32,
+    /// The radius of the bottom-right corner
+    pub bottom_right: f32,
+    /// The radius of the bottom-left corner
+    pub bottom_left: f32,
+}
+
+impl Radii {
+    /// Creates a new Radii object
+    pub fn new(top_left: f32, top_right: f32, bottom_right: f32, bottom_left: f32) -> Radii {
+        Radii {
+            top_left,
+            top_right,
+            bottom_right,
+            bottom_left,
+        }
+    }
+
+    /// Creates a Radii object with the same radius for every corner
+    pub fn uniform(radius: f32) -> Radii {
+        Radii::new(radius, radius, radius, radius)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.top_left <= 0.0
+            && self.top_right <= 0.0
+            && self.bottom_right <= 0.0
+            && self.bottom_left <= 0.0
+    }
+}
+
+/// The line style of a side of a border, drawn the way CSS draws it. Gaps in dashed, dotted and
+/// double sides are left undrawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BorderStyle {
+    /// A single solid line
+    Solid,
+    /// Dashes three times as long as the side is thick, with gaps two thirds of that
+    Dashed,
+    /// Round dots as wide as the side is thick, one thickness apart
+    Dotted,
+    /// Two solid lines, each a third of the thickness of the side
+    Double,
+    /// Looks carved into the canvas, the outer half is darker on the top and left and lighter
+    /// on the bottom and right than the inner half
+    Groove,
+    /// Looks raised from the canvas, the opposite of `Groove`
+    Ridge,
+    /// Makes the rectangle look sunken, the top and left are darker than the bottom and right
+    Inset,
+    /// Makes the rectangle look raised, the opposite of `Inset`
+    Outset,
+}
+
+impl Default for BorderStyle {
+    fn default() -> BorderStyle {
+        BorderStyle::Solid
+    }
+}
+
+/// One side of the border of a rectangle
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BorderSide {
+    /// The thickness of the side, the side is not drawn when zero
+    pub width: usize,
+    /// The paint of the side, sides with 3D styles are drawn in lighter and darker shades of it
+    pub color: Paint,
+    /// The line style of the side
+    pub style: BorderStyle,
+}
+
+impl BorderSide {
+    /// Creates a new solid BorderSide object with a single color
+    pub fn new(width: usize, color: [u8; 4]) -> BorderSide {
+        BorderSide {
+            width,
+            color: Paint::Solid(color),
+            style: BorderStyle::Solid,
+        }
+    }
+}
+
+/// The border drawn around the perimeter of a rectangle, every side has its own thickness and
+/// color. Where two sides of different colors meet the corner is split between them along the
+/// line from the outer to the inner corner of the border
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Border {
+    /// The top side of the border
+    pub top: BorderSide,
+    /// The right side of the border
+    pub right: BorderSide,
+    /// The bottom side of the border
+    pub bottom: BorderSide,
+    /// The left side of the border
+    pub left: BorderSide,
+}
+
+impl Border {
+    /// Creates a new Border object
+    pub fn new(top: BorderSide, right: BorderSide, bottom: BorderSide, left: BorderSide) -> Border {
+        Border {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+
+    /// Creates a Border object with the same thickness and color on the given sides and no
+    /// border on the others
+    pub fn uniform(width: usize, color: [u8; 4], sides: Sides) -> Border {
+        let side = |flag: Sides| {
+            if sides.contains(flag) {
+                BorderSide::new(width, color)
+            } else {
+                BorderSide::new(0, color)
+            }
+        };
+        Border::new(
+            side(Sides::TOP),
+            side(Sides::RIGHT),
+            side(Sides::BOTTOM),
+            side(Sides::LEFT),
+        )
+    }
+
+    /// Sets the line style of every side
+    pub fn set_style(&mut self, style: BorderStyle) {
+        self.top.style = style;
+        self.right.style = style;
+        self.bottom.style = style;
+        self.left.style = style;
+    }
+
+    /// Returns the sides clockwise from the top
+    fn sides(&self) -> [&BorderSide; 4] {
+        [&self.top, &self.right, &self.bottom, &self.left]
+    }
+}
+
+/// A soft shadow cast by a rectangle, drawn around it like a CSS box shadow. It follows the
+/// rounded corners of the rectangle and is never drawn underneath it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shadow {
+    /// How far the shadow is moved from the rectangle
+    pub offset: (f32, f32),
+    /// The blur radius of the shadow, like in CSS its edges are blurred with a standard
+    /// deviation of half the blur radius. Radii past 256 pixels are drawn as 256
+    pub blur: f32,
+    /// How far the shadow grows beyond the rectangle on every side before it is blurred, a
+    /// negative spread shrinks it
+    pub spread: f32,
+    /// The color of the shadow
+    pub color: [u8; 4],
+}
+
+impl Shadow {
+    /// Creates a new Shadow object
+    pub fn new(offset: (f32, f32), blur: f32, spread: f32, color: [u8; 4]) -> Shadow {
+        Shadow {
+            offset,
+            blur,
+            spread,
+            color,
+        }
+    }
+}
+
 /// A drawable object that represents a rectangle
 pub struct Rectangle {
     /// Position of the top-left corner of rectangle
     pub pos: (usize, usize),
     /// The size of the rectangle to be drawn, the border will be contained within this size
     pub size: (usize, usize),
-    /// The border that is drawn around the perimeter of the rectangle. It's arguments are
-    /// thickness of border, color of border, sides that the border is drawn around, rounding size
-    /// of the corners
-    pub border: Option<(usize, [u8; 4], Sides, Option<usize>)>,
-    /// The color of the fill (area) of the rectangle
-    pub fill: Option<[u8; 4]>,
+    /// The border that is drawn around the perimeter of the rectangle
+    pub border: Option<Border>,
+    /// The paint of the fill (area) of the rectangle
+    pub fill: Option<Paint>,
+    /// The rounding radii of the corners, both the border and the fill follow them
+    pub radii: Radii,
+    /// Decides whether rounded corners will be antialiased, the canvas' hints decide when `None`
+    pub antialiased: Option<bool>,
+    /// The shadow cast by the rectangle, which is drawn before it
+    pub shadow: Option<Shadow>,
 }
 
 impl Rectangle {
-    /// Creates a new Rectangle object
+    /// Creates a new Rectangle object with square corners and a solid fill
     pub fn new(
         pos: (usize, usize),
         size: (usize, usize),
-        border: Option<(usize, [u8; 4], Sides, Option<usize>)>,
+        border: Option<Border>,
         fill: Option<[u8; 4]>,
     ) -> Rectangle {
         Rectangle {
             pos,
             size,
             border,
-            fill,
+            fill: fill.map(Paint::Solid),
+            radii: Radii::default(),
+            antialiased: None,
+            shadow: None,
         }
     }
 
-    fn draw_borders(&self, canvas: &mut Canvas) {
-        if let Some(border) = self.border {
-            for i in 0..=border.0 {
-                let rounding_space = if let Some(round_size) = border.3 {
-                    if i < round_size {
-                        round_size
-                            - ((round_size as f32).powi(2) - ((round_size - i - 1) as f32).powi(2))
-                                .sqrt()
-                                .round() as usize
-                    } else {
-                        0
-                    }
-                } else {
-                    0
-                };
-
-                // Top line
-                if border.2.contains(Sides::TOP)
-                    && canvas.width > rounding_space * 2
-                    && self.pos.1 + i <= canvas.height - 1
-                {
-                    for x in self.pos.0 + rounding_space
-                        ..=min(
-                            self.pos.0 + self.size.0 - rounding_space - 1,
-                            canvas.width - 1,
-                        )
-                    {
-                        canvas.draw_point(x, self.pos.1 + i, border.1)
-                    }
-                }
-                // Bottom line
-                if border.2.contains(Sides::BOTTOM)
-                    && canvas.width > rounding_space * 2
-                    && self.pos.1 + self.size.1 - i - 1 <= canvas.height - 1
-                {
-                    for x in self.pos.0 + rounding_space
-                        ..=min(
-                            self.pos.0 + self.size.0 - rounding_space - 1,
-                            canvas.width - 1,
-                        )
-                    {
-                        canvas.draw_point(x, self.pos.1 + self.size.1 - i - 1, border.1)
-                    }
-                }
-                // Left line
-                if border.2.contains(Sides::LEFT)
-                    && canvas.height > rounding_space * 2
-                    && self.pos.0 + i <= canvas.width - 1
-                {
-                    for y in self.pos.1 + rounding_space
-                        ..=min(
-                            self.pos.1 + self.size.1 - rounding_space - 1,
-                            canvas.height - 1,
-                        )
-                    {
-                        canvas.draw_point(self.pos.0 + i, y, border.1)
+    /// Returns the sides of the border clockwise from the top, all of them without thickness
+    /// when there is no border
+    fn border_sides(&self) -> [BorderSide; 4] {
+        let [top, right, bottom, left] = match self.border {
+            Some(ref border) => border.sides(),
+            None => return Default::default(),
+        };
+        [top.clone(), right.clone(), bottom.clone(), left.clone()]
+    }
+
+    /// Returns whether the rectangle can be drawn by filling spans of pixels, which needs
+    /// square corners, a solid fill and a solid border of a single color
+    fn is_plain(&self) -> bool {
+        let sides = self.border_sides();
+        let mut visible = sides.iter().filter(|side| side.width > 0);
+        let border_plain = match visible.next() {
+            Some(first) => {
+                first.style == BorderStyle::Solid
+                    && is_solid(&first.color)
+                    && visible.all(|side| side.style == first.style && side.color == first.color)
+            }
+            None => true,
+        };
+        let fill_plain = self.fill.as_ref().map_or(true, is_solid);
+        self.radii.is_zero() && border_plain && fill_plain
+    }
+
+    /// Draws a rectangle with square corners and a border of one color, which always lines up
+    /// with whole pixels, by filling spans of pixels directly. Every pixel is drawn once even
+    /// where sides meet
+    fn draw_square(&self, canvas: &mut Canvas) {
+        let solid = |paint: &Paint| paint.color_at(0.0, 0.0);
+        let [top, right, bottom, left] = self.border_sides();
+        let (x, y) = self.pos;
+        let (width, height) = self.size;
+        let top_width = top.width.min(height);
+        let bottom_width = bottom.width.min(height - top_width);
+        let inner_height = height - top_width - bottom_width;
+        let left_width = left.width.min(width);
+        let right_width = right.width.min(width - left_width);
+        canvas.fill_rect((x, y), (width, top_width), solid(&top.color));
+        canvas.fill_rect(
+            (x, y + height - bottom_width),
+            (width, bottom_width),
+            solid(&bottom.color),
+        );
+        canvas.fill_rect(
+            (x, y + top_width),
+            (left_width, inner_height),
+            solid(&left.color),
+        );
+        canvas.fill_rect(
+            (x + width - right_width, y + top_width),
+            (right_width, inner_height),
+            solid(&right.color),
+        );
+        if let Some(ref fill) = self.fill {
+            canvas.fill_rect(
+                (x + left_width, y + top_width),
+                (width - left_width - right_width, inner_height),
+                solid(fill),
+            );
+        }
+    }
+
+    /// Draws a rectangle by rasterizing the outline of its fill and of each side of its border,
+    /// which is needed for rounded corners, styled borders and corners where sides of different
+    /// colors meet. The parts that line up with whole pixels are filled in spans
+    fn draw_outlined(&self, canvas: &mut Canvas) {
+        let sides = self.border_sides();
+        let (x, y) = (self.pos.0 as f32 - 0.5, self.pos.1 as f32 - 0.5);
+        let (width, height) = (self.size.0 as f32, self.size.1 as f32);
+        let radii = self.scaled_radii();
+        let widths = [
+            (sides[0].width as f32).min(height),
+            (sides[1].width as f32).min(width),
+            (sides[2].width as f32).min(height),
+            (sides[3].width as f32).min(width),
+        ];
+        let [top, right, bottom, left] = widths;
+
+        let outer = self.outline();
+        // Inner corners are the outer corners shrunk by the borders they touch, so they turn
+        // elliptical where the two borders differ in thickness
+        let inner_radius = |radius: f32, horizontal: f32, vertical: f32| {
+            ((radius - horizontal).max(0.0), (radius - vertical).max(0.0))
+        };
+        let inner = Outline {
+            pos: (x + left.min(width - right), y + top.min(height - bottom)),
+            size: (
+                (width - left - right).max(0.0),
+                (height - top - bottom).max(0.0),
+            ),
+            radii: [
+                inner_radius(radii[0], left, top),
+                inner_radius(radii[1], right, top),
+                inner_radius(radii[2], right, bottom),
+                inner_radius(radii[3], left, bottom),
+            ],
+        };
+
+        let fill = self.fill.as_ref().map(|fill| {
+            let mut rasterizer = Rasterizer::new();
+            rasterizer.add_polygon(&inner.points());
+            (rasterizer, fill)
+        });
+        // Every corner is split between the side before and the side after it, at an angle
+        // that follows the ratio of their thicknesses like a mitre does
+        let mut splits = [0.0; 4];
+        for (corner, split) in splits.iter_mut().enumerate() {
+            *split = widths[(corner + 3) % 4].atan2(widths[corner]);
+        }
+        let mut side_rasterizers = Vec::with_capacity(4);
+        for (side, border_side) in sides.iter().enumerate() {
+            side_rasterizers.push(if widths[side] > 0.0 {
+                let rungs = side_rungs(&outer, &inner, side, splits);
+                side_layers(&rungs, side, widths[side], border_side)
+            } else {
+                Vec::new()
+            });
+        }
+
+        let antialias = self.antialiased.unwrap_or(canvas.hints.antialias);
+        let layers: Vec<(&Rasterizer, &Paint)> = fill
+            .iter()
+            .map(|layer| (&layer.0, layer.1))
+            .chain(
+                side_rasterizers
+                    .iter()
+                    .flatten()
+                    .map(|layer| (&layer.0, &layer.1)),
+            )
+            .collect();
+        let (x, y) = self.pos;
+        let (width, height) = self.size;
+        let whole = ((x, y), (x + width, y + height));
+
+        // The curves and the splits between sides stay within a box in each corner, as wide and
+        // as tall as its radius or the border it touches. Only those boxes are rasterized, the
+        // rest of the rectangle lines up with whole pixels
+        let border_widths = [
+            widths[0] as usize,
+            widths[1] as usize,
+            widths[2] as usize,
+            widths[3] as usize,
+        ];
+        let [top, right, bottom, left] = border_widths;
+        let corner_box = |corner: usize, horizontal: usize, vertical: usize| {
+            (
+                (radii[corner].ceil() as usize).max(horizontal),
+                (radii[corner].ceil() as usize).max(vertical),
+            )
+        };
+        let corners = [
+            corner_box(0, left, top),
+            corner_box(1, right, top),
+            corner_box(2, right, bottom),
+            corner_box(3, left, bottom),
+        ];
+        if corners[0].0 + corners[1].0 > width
+            || corners[3].0 + corners[2].0 > width
+            || corners[0].1 + corners[3].1 > height
+            || corners[1].1 + corners[2].1 > height
+        {
+            fill_layers(canvas, &layers, whole, antialias);
+            return;
+        }
+        let (x_end, y_end) = (x + width, y + height);
+        let corner_areas = [
+            ((x, y), (x + corners[0].0, y + corners[0].1)),
+            ((x_end - corners[1].0, y), (x_end, y + corners[1].1)),
+            ((x_end - corners[2].0, y_end - corners[2].1), (x_end, y_end)),
+            ((x, y_end - corners[3].1), (x + corners[3].0, y_end)),
+        ];
+        for &area in &corner_areas {
+            fill_layers(canvas, &layers, area, antialias);
+        }
+
+        // Between the corners every side is a band along the edge, which is only rasterized when
+        // its style varies along or across it
+        let side_areas = [
+            ((x + corners[0].0, y), (x_end - corners[1].0, y + top)),
+            (
+                (x_end - right, y + corners[1].1),
+                (x_end, y_end - corners[2].1),
+            ),
+            (
+                (x + corners[3].0, y_end - bottom),
+                (x_end - corners[2].0, y_end),
+            ),
+            ((x, y + corners[0].1), (x + left, y_end - corners[3].1)),
+        ];
+        for (side, layers) in side_rasterizers.iter().enumerate() {
+            match sides[side].style {
+                BorderStyle::Solid | BorderStyle::Inset | BorderStyle::Outset => {
+                    if let Some(layer) = layers.first() {
+                        fill_span(canvas, side_areas[side], &layer.1);
                     }
                 }
-                // Right line
-                if border.2.contains(Sides::RIGHT)
-                    && canvas.height > rounding_space * 2
-                    && self.pos.0 + self.size.0 - i - 1 <= canvas.width - 1
-                {
-                    for y in self.pos.1 + rounding_space
-                        ..=min(
-                            self.pos.1 + self.size.1 - rounding_space - 1,
-                            canvas.height - 1,
-                        )
-                    {
-                        canvas.draw_point(self.pos.0 + self.size.0 - i - 1, y, border.1)
-                    }
+                _ => {
+                    let layers: Vec<(&Rasterizer, &Paint)> =
+                        layers.iter().map(|layer| (&layer.0, &layer.1)).collect();
+                    fill_layers(canvas, &layers, side_areas[side], antialias);
                 }
             }
         }
+
+        // The fill within the border is cut into bands of rows, which begin and end at the same
+        // columns, wherever the top or the bottom of a corner box lies
+        let fill = match self.fill {
+            Some(ref fill) => fill,
+            None => return,
+        };
+        let (fill_top, fill_bottom) = (top, height - bottom);
+        let mut rows = vec![
+            fill_top,
+            fill_bottom,
+            corners[0].1,
+            corners[1].1,
+            height - corners[2].1,
+            height - corners[3].1,
+        ];
+        for row in &mut rows {
+            *row = (*row).max(fill_top).min(fill_bottom);
+        }
+        rows.sort();
+        rows.dedup();
+        for band in rows.windows(2) {
+            let (from, to) = (band[0], band[1]);
+            let start = if from < corners[0].1 {
+                corners[0].0
+            } else if from >= height - corners[3].1 {
+                corners[3].0
+            } else {
+                left
+            };
+            let end = if from < corners[1].1 {
+                width - corners[1].0
+            } else if from >= height - corners[2].1 {
+                width - corners[2].0
+            } else {
+                width - right
+            };
+            fill_span(canvas, ((x + start, y + from), (x + end, y + to)), fill);
+        }
+    }
+
+    /// Returns the outline of the whole rectangle, whose edges lie half a pixel outside of the
+    /// centers of its outermost pixels
+    fn outline(&self) -> Outline {
+        let radii = self.scaled_radii();
+        Outline {
+            pos: (self.pos.0 as f32 - 0.5, self.pos.1 as f32 - 0.5),
+            size: (self.size.0 as f32, self.size.1 as f32),
+            radii: [
+                (radii[0], radii[0]),
+                (radii[1], radii[1]),
+                (radii[2], radii[2]),
+                (radii[3], radii[3]),
+            ],
+        }
     }
 
-    fn draw_area(&self, canvas: &mut Canvas) {
-        if let Some(fill) = self.fill {
-            let (area_pos, area_size) = self.measure_area();
-            for y in area_pos.1..=min(area_pos.1 + area_size.1 - 1, canvas.height - 1) {
-                for x in area_pos.0..=min(area_pos.0 + area_size.0 - 1, canvas.width - 1) {
-                    canvas.draw_point(x, y, fill)
+    /// Draws the shadow of the rectangle by blurring the coverage of its grown and moved outline
+    /// and leaving out the area covered by the rectangle itself
+    fn draw_shadow(&self, canvas: &mut Canvas, shadow: &Shadow) {
+        let values = [shadow.offset.0, shadow.offset.1, shadow.blur, shadow.spread];
+        if shadow.color[0] == 0 || !values.iter().all(|v| v.is_finite()) {
+            return;
+        }
+        let caster = self.outline();
+        let spread = shadow.spread;
+        let mut radii = caster.radii;
+        for radius in &mut radii {
+            if radius.0 > 0.0 {
+                let grown = (radius.0 + spread).max(0.0);
+                *radius = (grown, grown);
+            }
+        }
+        let shape = Outline {
+            pos: (
+                caster.pos.0 + shadow.offset.0 - spread,
+                caster.pos.1 + shadow.offset.1 - spread,
+            ),
+            size: (caster.size.0 + 2.0 * spread, caster.size.1 + 2.0 * spread),
+            radii,
+        };
+        if shape.size.0 <= 0.0 || shape.size.1 <= 0.0 {
+            return;
+        }
+
+        // The shadow is rendered into a buffer that reaches far enough past the shape for the
+        // blur to fade out. It is cut off just as far past the edges of the canvas, since the
+        // shape beyond that does not blur into any visible pixel
+        let sigma = shadow.blur.max(0.0).min(MAX_SHADOW_BLUR) / 2.0;
+        let margin = (3.0 * sigma).ceil() + 1.0;
+        let canvas_size = (canvas.width as f32, canvas.height as f32);
+        let origin = (
+            (shape.pos.0 - margin).max(-margin).floor(),
+            (shape.pos.1 - margin).max(-margin).floor(),
+        );
+        let end = (
+            (shape.pos.0 + shape.size.0 + margin)
+                .min(canvas_size.0 + margin)
+                .ceil(),
+            (shape.pos.1 + shape.size.1 + margin)
+                .min(canvas_size.1 + margin)
+                .ceil(),
+        );
+        if end.0 <= origin.0 || end.1 <= origin.1 {
+            return;
+        }
+        let width = (end.0 - origin.0) as usize;
+        let height = (end.1 - origin.1) as usize;
+        let translated = |outline: &Outline| {
+            let mut rasterizer = Rasterizer::new();
+            let points: Vec<(f32, f32)> = outline
+                .points()
+                .iter()
+                .map(|p| (p.0 - origin.0, p.1 - origin.1))
+                .collect();
+            rasterizer.add_polygon(&points);
+            rasterizer
+        };
+        let samples = canvas.hints.antialias_quality.samples();
+        let mut mask = vec![0.0; width * height];
+        translated(&shape).fill((width, height), samples, true, |x, y, coverage| {
+            mask[y * width + x] = coverage;
+        });
+        gaussian_blur(&mut mask, width, height, sigma);
+        translated(&caster).fill((width, height), samples, true, |x, y, coverage| {
+            mask[y * width + x] *= 1.0 - coverage;
+        });
+
+        for y in 0..height {
+            let canvas_y = origin.1 as isize + y as isize;
+            if canvas_y < 0 || canvas_y >= canvas.height as isize {
+                continue;
+            }
+            for x in 0..width {
+                let canvas_x = origin.0 as isize + x as isize;
+                if canvas_x < 0 || canvas_x >= canvas.width as isize {
+                    continue;
+                }
+                let mut color = shadow.color;
+                color[0] = (f32::from(color[0]) * mask[y * width + x].min(1.0)) as u8;
+                if color[0] > 0 {
+                    canvas.draw_point(canvas_x as usize, canvas_y as usize, color);
                 }
             }
         }
     }
 
-    fn measure_area(&self) -> ((usize, usize), (usize, usize)) {
-        let (mut area_pos, mut area_size) = (self.pos, self.size);
-        if let Some(border) = self.border {
-            if border.2.contains(Sides::TOP) {
-                area_pos.1 += border.0;
-                area_size.1 -= border.0;
+    /// Returns the corner radii clockwise from the top-left, scaled down evenly if any two
+    /// neighbouring radii would not fit along the side between them
+    fn scaled_radii(&self) -> [f32; 4] {
+        let radii = [
+            self.radii.top_left.max(0.0),
+            self.radii.top_right.max(0.0),
+            self.radii.bottom_right.max(0.0),
+            self.radii.bottom_left.max(0.0),
+        ];
+        let (width, height) = (self.size.0 as f32, self.size.1 as f32);
+        let mut scale: f32 = 1.0;
+        for &(sum, length) in &[
+            (radii[0] + radii[1], width),
+            (radii[1] + radii[2], height),
+            (radii[2] + radii[3], width),
+            (radii[3] + radii[0], height),
+        ] {
+            if sum > length {
+                scale = scale.min(length / sum);
             }
-            if border.2.contains(Sides::BOTTOM) {
-                area_size.1 -= border.0;
+        }
+        [
+            radii[0] * scale,
+            radii[1] * scale,
+            radii[2] * scale,
+            radii[3] * scale,
+        ]
+    }
+}
+
+/// The largest blur radius of a shadow, which keeps the buffer it is blurred in small
+const MAX_SHADOW_BLUR: f32 = 256.0;
+
+/// The outline of a rectangle whose corners, clockwise from the top-left, are rounded with the
+/// given horizontal and vertical radii
+struct Outline {
+    pos: (f32, f32),
+    size: (f32, f32),
+    radii: [(f32, f32); 4],
+}
+
+impl Outline {
+    fn points(&self) -> Vec<(f32, f32)> {
+        let mut points = Vec::new();
+        for corner in 0..4 {
+            for angle in arc_angles(self.radii[corner], 0.0, PI / 2.0) {
+                points.push(self.corner_point(corner, angle));
             }
-            if border.2.contains(Sides::LEFT) {
-                area_pos.0 += border.0;
-                area_size.0 -= border.0;
+        }
+        points
+    }
+
+    /// Returns the point on a corner at an angle between zero and a quarter turn, measured
+    /// clockwise from where the corner leaves the side before it
+    fn corner_point(&self, corner: usize, angle: f32) -> (f32, f32) {
+        let (left, top) = self.pos;
+        let (right, bottom) = (left + self.size.0, top + self.size.1);
+        let radius = self.radii[corner];
+        let center = match corner {
+            0 => (left + radius.0, top + radius.1),
+            1 => (right - radius.0, top + radius.1),
+            2 => (right - radius.0, bottom - radius.1),
+            _ => (left + radius.0, bottom - radius.1),
+        };
+        let angle = PI + corner as f32 * PI / 2.0 + angle;
+        (
+            center.0 + radius.0 * angle.cos(),
+            center.1 + radius.1 * angle.sin(),
+        )
+    }
+}
+
+/// A pair of matching points on the outer and the inner edge of a side of a border
+type Rung = ((f32, f32), (f32, f32));
+
+/// Returns the rungs along one side of a border, counting sides clockwise from the top, from
+/// where it meets the side before it to where it meets the side after it
+fn side_rungs(outer: &Outline, inner: &Outline, side: usize, splits: [f32; 4]) -> Vec<Rung> {
+    let next = (side + 1) % 4;
+    let mut rungs = Vec::new();
+    for &(corner, from, to) in &[(side, splits[side], PI / 2.0), (next, 0.0, splits[next])] {
+        for angle in arc_angles(outer.radii[corner], from, to) {
+            rungs.push((
+                outer.corner_point(corner, angle),
+                inner.corner_point(corner, angle),
+            ));
+        }
+    }
+    rungs
+}
+
+fn lerp(p0: (f32, f32), p1: (f32, f32), t: f32) -> (f32, f32) {
+    (p0.0 + (p1.0 - p0.0) * t, p0.1 + (p1.1 - p0.1) * t)
+}
+
+/// Returns the outline of the band between two fractions of the thickness of a side, where zero
+/// is its outer and one its inner edge
+fn band(rungs: &[Rung], from: f32, to: f32) -> Vec<(f32, f32)> {
+    let mut points: Vec<(f32, f32)> = rungs.iter().map(|r| lerp(r.0, r.1, from)).collect();
+    points.extend(rungs.iter().rev().map(|r| lerp(r.0, r.1, to)));
+    points
+}
+
+/// Returns the rungs between two distances along the middle of a side
+fn rungs_between(rungs: &[Rung], lengths: &[f32], from: f32, to: f32) -> Vec<Rung> {
+    let at = |distance: f32| {
+        let i = lengths
+            .iter()
+            .position(|&length| length >= distance)
+            .unwrap_or(lengths.len() - 1)
+            .max(1);
+        let span = lengths[i] - lengths[i - 1];
+        let t = if span > 0.0 {
+            ((distance - lengths[i - 1]) / span).max(0.0).min(1.0)
+        } else {
+            0.0
+        };
+        let (r0, r1) = (rungs[i - 1], rungs[i]);
+        (lerp(r0.0, r1.0, t), lerp(r0.1, r1.1, t))
+    };
+    let mut between = vec![at(from)];
+    for (rung, &length) in rungs.iter().zip(lengths) {
+        if length > from && length < to {
+            between.push(*rung);
+        }
+    }
+    between.push(at(to));
+    between
+}
+
+/// Fills a rectangle of pixels with a paint, a single color is filled in spans
+fn fill_span(canvas: &mut Canvas, area: PixelArea, paint: &Paint) {
+    let ((x_start, y_start), (x_end, y_end)) = area;
+    if x_end <= x_start || y_end <= y_start {
+        return;
+    }
+    if let Paint::Solid(color) = *paint {
+        canvas.fill_rect(
+            (x_start, y_start),
+            (x_end - x_start, y_end - y_start),
+            color,
+        );
+        return;
+    }
+    let (x_end, y_end) = (x_end.min(canvas.width), y_end.min(canvas.height));
+    let mut row = Vec::with_capacity(x_end.saturating_sub(x_start));
+    for y in y_start..y_end {
+        row.clear();
+        row.extend((x_start..x_end).map(|x| paint.color_at(x as f32, y as f32)));
+        canvas.draw_row((x_start, y), &row);
+    }
+}
+
+/// Returns whether a paint is a single color
+fn is_solid(paint: &Paint) -> bool {
+    match *paint {
+        Paint::Solid(_) => true,
+        _ => false,
+    }
+}
+
+/// Returns a lighter or darker shade of a paint for the 3D border styles
+fn shade(paint: &Paint, dark: bool) -> Paint {
+    paint.map_colors(|color| {
+        let mut shaded = color;
+        for channel in &mut shaded[1..] {
+            *channel = if dark {
+                (u16::from(*channel) * 2 / 3) as u8
+            } else {
+                *channel + (255 - *channel) / 3
+            };
+        }
+        shaded
+    })
+}
+
+/// Returns the rasterized outlines of one side of a border and their colors
+fn side_layers(
+    rungs: &[Rung],
+    side: usize,
+    width: f32,
+    border_side: &BorderSide,
+) -> Vec<(Rasterizer, Paint)> {
+    let color = &border_side.color;
+    // The top and left sides are lit, the bottom and right sides are in shadow
+    let lit = side == 0 || side == 3;
+    let mut layers = Vec::new();
+    let mut layer = |bands: &[(f32, f32)], paint: Paint| {
+        let mut rasterizer = Rasterizer::new();
+        for &(from, to) in bands {
+            rasterizer.add_polygon(&band(rungs, from, to));
+        }
+        layers.push((rasterizer, paint));
+    };
+    match border_side.style {
+        BorderStyle::Solid => layer(&[(0.0, 1.0)], color.clone()),
+        BorderStyle::Double => layer(&[(0.0, 1.0 / 3.0), (2.0 / 3.0, 1.0)], color.clone()),
+        BorderStyle::Inset => layer(&[(0.0, 1.0)], shade(color, lit)),
+        BorderStyle::Outset => layer(&[(0.0, 1.0)], shade(color, !lit)),
+        BorderStyle::Groove => {
+            layer(&[(0.0, 0.5)], shade(color, lit));
+            layer(&[(0.5, 1.0)], shade(color, !lit));
+        }
+        BorderStyle::Ridge => {
+            layer(&[(0.0, 0.5)], shade(color, !lit));
+            layer(&[(0.5, 1.0)], shade(color, lit));
+        }
+        BorderStyle::Dashed | BorderStyle::Dotted => {
+            let mut lengths = Vec::with_capacity(rungs.len());
+            let mut length = 0.0;
+            for (i, rung) in rungs.iter().enumerate() {
+                if i > 0 {
+                    let p0 = lerp(rungs[i - 1].0, rungs[i - 1].1, 0.5);
+                    let p1 = lerp(rung.0, rung.1, 0.5);
+                    length += ((p1.0 - p0.0).powi(2) + (p1.1 - p0.1).powi(2)).sqrt();
+                }
+                lengths.push(length);
             }
-            if border.2.contains(Sides::RIGHT) {
-                area_size.0 -= border.0;
+            // Dashes and dots are centered in equal periods stretched to fit the side exactly,
+            // so neighbouring sides never draw over each other
+            let dotted = border_side.style == BorderStyle::Dotted;
+            let period = if dotted { 2.0 * width } else { 5.0 * width };
+            let count = (length / period).round().max(1.0);
+            let period = length / count;
+            let mut rasterizer = Rasterizer::new();
+            for i in 0..count as usize {
+                let center = (i as f32 + 0.5) * period;
+                if dotted {
+                    let rung = rungs_between(rungs, &lengths, center, center)[0];
+                    let radius = width / 2.0;
+                    rasterizer.add_ellipse(lerp(rung.0, rung.1, 0.5), (radius, radius));
+                } else {
+                    let half = period * 0.3;
+                    let dash = rungs_between(rungs, &lengths, center - half, center + half);
+                    rasterizer.add_polygon(&band(&dash, 0.0, 1.0));
+                }
             }
+            layers.push((rasterizer, color.clone()));
         }
-        (area_pos, area_size)
     }
+    layers
 }
 
 impl Drawable for Rectangle {
     fn draw(&self, canvas: &mut Canvas) {
-        self.draw_borders(canvas);
-        self.draw_area(canvas);
+        if let Some(ref shadow) = self.shadow {
+            self.draw_shadow(canvas, shadow);
+        }
+        if self.is_plain() {
+            self.draw_square(canvas);
+        } else {
+            self.draw_outlined(canvas);
+        }
     }
 }
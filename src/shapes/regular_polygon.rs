@@ -0,0 +1,87 @@
+use std::f32::consts::PI;
+
+use paint::Paint;
+use shapes::polygon::{FillRule, Polygon};
+use Canvas;
+use Drawable;
+
+/// A drawable object that represents a polygon with equal sides and equal angles. Angles are in
+/// radians, start at the positive x axis and increase clockwise
+pub struct RegularPolygon {
+    /// Position of the center of the polygon
+    pub center: (f32, f32),
+    /// The distance from the center to each corner
+    pub radius: f32,
+    /// The number of sides, nothing is drawn with less than three. Sides shorter than a quarter
+    /// of a pixel look the same as fewer sides and are merged
+    pub sides: usize,
+    /// The angle of the first corner as seen from the center
+    pub rotation: f32,
+    /// The stroke that is drawn along the edges of the polygon, centered on them. It's arguments
+    /// are width of stroke and paint of stroke
+    pub stroke: Option<(f32, Paint)>,
+    /// The paint of the fill (area) of the polygon
+    pub fill: Option<Paint>,
+    /// Decides whether the polygon will be antialiased, the canvas' hints decide when `None`
+    pub antialiased: Option<bool>,
+}
+
+impl RegularPolygon {
+    /// Creates a new RegularPolygon object with a solid stroke and fill
+    pub fn new(
+        center: (f32, f32),
+        radius: f32,
+        sides: usize,
+        rotation: f32,
+        stroke: Option<(f32, [u8; 4])>,
+        fill: Option<[u8; 4]>,
+    ) -> RegularPolygon {
+        RegularPolygon {
+            center,
+            radius,
+            sides,
+            rotation,
+            stroke: stroke.map(|(width, color)| (width, Paint::Solid(color))),
+            fill: fill.map(Paint::Solid),
+            antialiased: None,
+        }
+    }
+}
+
+impl Drawable for RegularPolygon {
+    fn draw(&self, canvas: &mut Canvas) {
+        if self.sides < 3 {
+            return;
+        }
+        let sides = self.sides.min(max_corners(self.radius));
+        let points = (0..sides)
+            .map(|i| {
+                let angle = self.rotation + 2.0 * PI * i as f32 / sides as f32;
+                (
+                    self.center.0 + self.radius * angle.cos(),
+                    self.center.1 + self.radius * angle.sin(),
+                )
+            })
+            .collect();
+        let polygon = Polygon {
+            points,
+            stroke: self.stroke.clone(),
+            fill: self.fill.clone(),
+            fill_rule: FillRule::NonZero,
+            antialiased: self.antialiased,
+        };
+        polygon.draw(canvas);
+    }
+}
+
+/// Returns how many corners can be spread around a circle of `radius` before neighbouring
+/// corners lie within a quarter of a pixel of each other, and never less than three
+pub(crate) fn max_corners(radius: f32) -> usize {
+    // Keeps the number of edges reasonable for huge circles, which are mostly off the canvas
+    const LIMIT: f32 = 65536.0;
+    let corners = (8.0 * PI * radius.abs()).ceil();
+    if corners.is_nan() {
+        return 3;
+    }
+    corners.max(3.0).min(LIMIT) as usize
+}
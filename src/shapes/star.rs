@@ -0,0 +1,87 @@
+use std::f32::consts::PI;
+
+use paint::Paint;
+use shapes::polygon::{FillRule, Polygon};
+use shapes::regular_polygon::max_corners;
+use Canvas;
+use Drawable;
+
+/// A drawable object that represents a star, whose outline alternates between points on an outer
+/// and notches on an inner circle. Angles are in radians, start at the positive x axis and
+/// increase clockwise
+pub struct Star {
+    /// Position of the center of the star
+    pub center: (f32, f32),
+    /// The number of points, nothing is drawn with less than two. Points narrower than a quarter
+    /// of a pixel look the same as fewer points and are merged
+    pub points: usize,
+    /// The distance from the center to the tips of the points
+    pub outer_radius: f32,
+    /// The distance from the center to the notches between the points
+    pub inner_radius: f32,
+    /// The angle of the first point as seen from the center
+    pub rotation: f32,
+    /// The stroke that is drawn along the edges of the star, centered on them. It's arguments are
+    /// width of stroke and paint of stroke
+    pub stroke: Option<(f32, Paint)>,
+    /// The paint of the fill (area) of the star
+    pub fill: Option<Paint>,
+    /// Decides whether the star will be antialiased, the canvas' hints decide when `None`
+    pub antialiased: Option<bool>,
+}
+
+impl Star {
+    /// Creates a new Star object with a solid stroke and fill
+    pub fn new(
+        center: (f32, f32),
+        points: usize,
+        outer_radius: f32,
+        inner_radius: f32,
+        rotation: f32,
+        stroke: Option<(f32, [u8; 4])>,
+        fill: Option<[u8; 4]>,
+    ) -> Star {
+        Star {
+            center,
+            points,
+            outer_radius,
+            inner_radius,
+            rotation,
+            stroke: stroke.map(|(width, color)| (width, Paint::Solid(color))),
+            fill: fill.map(Paint::Solid),
+            antialiased: None,
+        }
+    }
+}
+
+impl Drawable for Star {
+    fn draw(&self, canvas: &mut Canvas) {
+        if self.points < 2 {
+            return;
+        }
+        let radius = self.outer_radius.abs().max(self.inner_radius.abs());
+        let corners = 2 * self.points.min(max_corners(radius) / 2);
+        let points = (0..corners)
+            .map(|i| {
+                let angle = self.rotation + 2.0 * PI * i as f32 / corners as f32;
+                let radius = if i % 2 == 0 {
+                    self.outer_radius
+                } else {
+                    self.inner_radius
+                };
+                (
+                    self.center.0 + radius * angle.cos(),
+                    self.center.1 + radius * angle.sin(),
+                )
+            })
+            .collect();
+        let polygon = Polygon {
+            points,
+            stroke: self.stroke.clone(),
+            fill: self.fill.clone(),
+            fill_rule: FillRule::NonZero,
+            antialiased: self.antialiased,
+        };
+        polygon.draw(canvas);
+    }
+}
@@ -0,0 +1,46 @@
+use paint::Paint;
+use shapes::polygon::{FillRule, Polygon};
+use Canvas;
+use Drawable;
+
+/// A drawable object that represents a triangle
+pub struct Triangle {
+    /// The corners of the triangle
+    pub points: [(f32, f32); 3],
+    /// The stroke that is drawn along the edges of the triangle, centered on them. It's arguments
+    /// are width of stroke and paint of stroke
+    pub stroke: Option<(f32, Paint)>,
+    /// The paint of the fill (area) of the triangle
+    pub fill: Option<Paint>,
+    /// Decides whether the triangle will be antialiased, the canvas' hints decide when `None`
+    pub antialiased: Option<bool>,
+}
+
+impl Triangle {
+    /// Creates a new Triangle object with a solid stroke and fill
+    pub fn new(
+        points: [(f32, f32); 3],
+        stroke: Option<(f32, [u8; 4])>,
+        fill: Option<[u8; 4]>,
+    ) -> Triangle {
+        Triangle {
+            points,
+            stroke: stroke.map(|(width, color)| (width, Paint::Solid(color))),
+            fill: fill.map(Paint::Solid),
+            antialiased: None,
+        }
+    }
+}
+
+impl Drawable for Triangle {
+    fn draw(&self, canvas: &mut Canvas) {
+        let polygon = Polygon {
+            points: self.points.to_vec(),
+            stroke: self.stroke.clone(),
+            fill: self.fill.clone(),
+            fill_rule: FillRule::NonZero,
+            antialiased: self.antialiased,
+        };
+        polygon.draw(canvas);
+    }
+}
@@ -41,7 +41,7 @@ fn parse_config(path: &Path) -> Vec<(Vec<String>, String)> {
             Ok(XmlEvent::EndElement { .. }) => {
                 tracking_tags.pop();
             }
-            Err(e) => panic!(e),
+            Err(e) => panic!("{}", e),
             _ => {}
         }
     }
@@ -56,6 +56,7 @@ pub struct FontConfig {
 
 impl FontConfig {
     /// Creates a new FontConfig object by looking for the fontconfig config file
+    #[allow(clippy::result_unit_err)]
     pub fn new() -> Result<FontConfig, ()> {
         let location = get_config().ok_or(())?;
         let data = parse_config(&location);
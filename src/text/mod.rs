@@ -7,6 +7,7 @@ use std::io::Read;
 use std::path::PathBuf;
 use Canvas;
 use Drawable;
+use TextAntialias;
 
 /// A drawable object that represents text
 pub struct Text<'a> {
@@ -22,6 +23,8 @@ pub struct Text<'a> {
     pub scale: Scale,
     /// The vertical metrics of the text
     pub v_metrics: VMetrics,
+    /// Decides how the text is antialiased, the canvas' hints decide when `None`
+    pub antialias: Option<TextAntialias>,
 }
 
 /// Loads a font file into a `Vec<u8>`
@@ -60,13 +63,31 @@ impl<'a> Text<'a> {
             scale,
             v_metrics,
             font,
+            antialias: None,
         }
     }
 
     fn draw_text(&self, canvas: &mut Canvas) {
+        let antialias = self.antialias.unwrap_or(canvas.hints.text_antialias);
+        let snap = canvas.hints.pixel_snap;
+        let baseline = if snap {
+            self.v_metrics.ascent.round()
+        } else {
+            self.v_metrics.ascent
+        };
         let glyphs: Vec<_> = self
             .font
-            .layout(&self.text, self.scale, point(0.0, self.v_metrics.ascent))
+            .layout(&self.text, self.scale, point(0.0, baseline))
+            .map(|glyph| {
+                if snap {
+                    let position = glyph.position();
+                    glyph
+                        .into_unpositioned()
+                        .positioned(point(position.x.round(), position.y))
+                } else {
+                    glyph
+                }
+            })
             .collect();
         for glyph in glyphs {
             if let Some(bounding_box) = glyph.pixel_bounding_box() {
@@ -76,8 +97,17 @@ impl<'a> Text<'a> {
 
                     if x < canvas.width && y < canvas.height {
                         let mut color = self.color;
-                        color[0] = (f32::from(color[0]) * v) as u8;
-                        canvas.draw_point(x, y, color);
+                        match antialias {
+                            TextAntialias::Grayscale => {
+                                color[0] = (f32::from(color[0]) * v) as u8;
+                                canvas.draw_point(x, y, color);
+                            }
+                            TextAntialias::Off => {
+                                if v >= 0.5 {
+                                    canvas.draw_point(x, y, color);
+                                }
+                            }
+                        }
                     }
                 });
             }